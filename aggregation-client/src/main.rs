@@ -12,13 +12,13 @@ use std::collections::HashMap;
 //     l1::{OracleBlobProvider, OracleL1ChainProvider},
 //     BootInfo,
 // };
+#[cfg(target_os = "zkvm")]
 use sha2::{Digest, Sha256};
 
-/// Note: This is the hardcoded program vkey for the multi-block program. Whenever the multi-block
-/// program changes, update this.
-const MULTI_BLOCK_PROGRAM_VKEY_DIGEST: [u32; 8] = [
-    1182742183, 831715190, 1934100752, 151832364, 1086222319, 415089854, 144530717, 984311993,
-];
+/// The vkey digest of the multi-block program. `zkvm-host/build.rs` computes it from the
+/// multi-block ELF before building this program, so it can't fall out of date with the program.
+#[cfg(target_os = "zkvm")]
+const MULTI_BLOCK_PROGRAM_VKEY_DIGEST: [u32; 8] = include!("../../elf/validity-client-vkey-digest");
 
 /// Verify that the L1 heads in the boot infos are in the header chain.
 fn verify_l1_heads(agg_inputs: &AggregationInputs, headers: &[Header]) {
@@ -70,21 +70,33 @@ pub fn main() {
         // The claimed block of the previous boot info must be the L2 output root of the current boot.
         assert_eq!(prev_boot_info.l2_claim, boot_info.l2_output_root);
 
+        // The claimed block of the previous boot info must be the starting block of the current
+        // boot info, so the ranges are contiguous.
+        assert_eq!(
+            prev_boot_info.l2_claim_block,
+            boot_info.l2_output_root_block
+        );
+
         // The chain ID must be the same for all the boot infos, to ensure they're
         // from the same chain and span batch range.
         assert_eq!(prev_boot_info.chain_id, boot_info.chain_id);
+
+        // The rollup config must be the same for all the boot infos, so that the aggregated
+        // proof commits to the single config the whole range was derived with.
+        assert_eq!(
+            prev_boot_info.rollup_config_hash,
+            boot_info.rollup_config_hash
+        );
     });
 
     // Verify each multi-block program proof.
+    #[cfg(target_os = "zkvm")]
     agg_inputs.boot_infos.iter().for_each(|boot_info| {
         // In the multi-block program, the public values digest is just the hash of the ABI encoded
         // boot info.
         let abi_encoded_boot_info = boot_info.abi_encode();
         let pv_digest = Sha256::digest(abi_encoded_boot_info);
-
-        if cfg!(target_os = "zkvm") {
-            sp1_lib::verify::verify_sp1_proof(&MULTI_BLOCK_PROGRAM_VKEY_DIGEST, &pv_digest.into());
-        }
+        sp1_lib::verify::verify_sp1_proof(&MULTI_BLOCK_PROGRAM_VKEY_DIGEST, &pv_digest.into());
     });
 
     // Verify the L1 heads of each boot info are on the L1.
@@ -96,10 +108,13 @@ pub fn main() {
     let final_boot_info = RawBootInfo {
         // The first boot info's L2 output root is the L2 output root of the range.
         l2_output_root: first_boot_info.l2_output_root,
+        l2_output_root_block: first_boot_info.l2_output_root_block,
         l2_claim_block: last_boot_info.l2_claim_block,
         l2_claim: last_boot_info.l2_claim,
         l1_head: last_boot_info.l1_head,
         chain_id: last_boot_info.chain_id,
+        rollup_config_hash: last_boot_info.rollup_config_hash,
+        rollup_config: None,
     };
 
     // Commit to the aggregated boot info.
@@ -49,16 +49,21 @@ contract ZKL2OutputOracle is Initializable, ISemver {
     ////////////////////////////////////////////////////////////
 
     /// @notice Struct containing the public values committed to for the SP1 proof.
+    /// @dev Must match `RawBootInfoStruct` in `crates/client-utils/src/boot.rs`.
     struct PublicValuesStruct {
+        uint256 version;
         bytes32 l1Head;
         bytes32 l2PreRoot;
+        uint256 l2PreBlockNum;
         bytes32 claimRoot;
         uint256 claimBlockNum;
         uint256 chainId;
+        bytes32 rollupConfigHash;
     }
 
     struct ZKInitParams {
         uint chainId;
+        bytes32 rollupConfigHash;
         bytes32 vkey;
         address verifierGateway;
         bytes32 startingOutputRoot;
@@ -80,6 +85,9 @@ contract ZKL2OutputOracle is Initializable, ISemver {
     /// @notice A trusted mapping of block numbers to block hashes.
     mapping (uint => bytes32) public historicBlockHashes;
 
+    /// @notice The hash of the rollup config of the L2 chain that proofs must be generated with.
+    bytes32 public rollupConfigHash;
+
     ////////////////////////////////////////////////////////////
     //                         Events                         //
     ////////////////////////////////////////////////////////////
@@ -103,6 +111,11 @@ contract ZKL2OutputOracle is Initializable, ISemver {
     /// @param newVkey The new vkey.
     event UpdatedVKey(bytes32 indexed oldVkey, bytes32 indexed newVkey);
 
+    /// @notice Emitted when the rollup config hash is updated.
+    /// @param oldRollupConfigHash The old rollup config hash.
+    /// @param newRollupConfigHash The new rollup config hash.
+    event UpdatedRollupConfigHash(bytes32 indexed oldRollupConfigHash, bytes32 indexed newRollupConfigHash);
+
     /// @notice Emitted when the verifier gateway is updated.
     /// @param oldVerifierGateway The old verifier gateway.
     /// @param newVerifierGateway The new verifier gateway.
@@ -113,9 +126,12 @@ contract ZKL2OutputOracle is Initializable, ISemver {
     /// @param newOwner      The new owner.
     event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
 
+    /// @notice The version of the public values layout that proofs must commit to.
+    uint256 public constant PUBLIC_VALUES_VERSION = 2;
+
     /// @notice Semantic version.
-    /// @custom:semver 2.0.0
-    string public constant version = "2.0.0";
+    /// @custom:semver 3.0.0
+    string public constant version = "3.0.0";
 
     ////////////////////////////////////////////////////////////
     //                        Modifiers                       //
@@ -144,7 +160,7 @@ contract ZKL2OutputOracle is Initializable, ISemver {
     /// @param _challenger          The address of the challenger.
     /// @param _finalizationPeriodSeconds The minimum time (in seconds) that must elapse before a withdrawal
     ///                                   can be finalized.
-    /// @param _zkInitParams        The chain ID, vkey, verifier gateway, owner, and starting output root for the ZK version of the contract.
+    /// @param _zkInitParams        The chain ID, rollup config hash, vkey, verifier gateway, owner, and starting output root for the ZK version of the contract.
     /// @dev Starting block number, timestamp and output root are ignored for upgrades where these values already exist.
     function initialize(
         uint256 _submissionInterval,
@@ -157,7 +173,7 @@ contract ZKL2OutputOracle is Initializable, ISemver {
         ZKInitParams memory _zkInitParams
     )
         public
-        reinitializer(2)
+        reinitializer(3)
     {
         require(_submissionInterval > 0, "L2OutputOracle: submission interval must be greater than 0");
         require(_l2BlockTime > 0, "L2OutputOracle: L2 block time must be greater than 0");
@@ -186,6 +202,7 @@ contract ZKL2OutputOracle is Initializable, ISemver {
         }
 
         chainId = _zkInitParams.chainId;
+        _updateRollupConfigHash(_zkInitParams.rollupConfigHash);
         _transferOwnership(_zkInitParams.owner);
         _updateVKey(_zkInitParams.vkey);
         _updateVerifierGateway(_zkInitParams.verifierGateway);
@@ -301,11 +318,14 @@ contract ZKL2OutputOracle is Initializable, ISemver {
         );
 
         PublicValuesStruct memory publicValues = PublicValuesStruct({
+            version: PUBLIC_VALUES_VERSION,
             l1Head: _l1BlockHash,
             l2PreRoot: l2Outputs[nextOutputIndex() - 1].outputRoot,
+            l2PreBlockNum: l2Outputs[nextOutputIndex() - 1].l2BlockNumber,
             claimRoot: _outputRoot,
             claimBlockNum: _l2BlockNumber,
-            chainId: chainId
+            chainId: chainId,
+            rollupConfigHash: rollupConfigHash
         });
 
         verifierGateway.verifyProof(vkey, abi.encode(publicValues), _proof);
@@ -436,6 +456,15 @@ contract ZKL2OutputOracle is Initializable, ISemver {
         vkey = _vkey;
     }
 
+    function updateRollupConfigHash(bytes32 _rollupConfigHash) external onlyOwner {
+        _updateRollupConfigHash(_rollupConfigHash);
+    }
+
+    function _updateRollupConfigHash(bytes32 _rollupConfigHash) internal {
+        emit UpdatedRollupConfigHash(rollupConfigHash, _rollupConfigHash);
+        rollupConfigHash = _rollupConfigHash;
+    }
+
     function updateVerifierGateway(address _verifierGateway) external onlyOwner {
         _updateVerifierGateway(_verifierGateway);
     }
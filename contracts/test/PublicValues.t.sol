@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import { Test } from "forge-std/Test.sol";
+import { ZKL2OutputOracle } from "src/ZKL2OutputOracle.sol";
+
+/// @notice Checks that the public values the contract verifies proofs against byte-match what the
+///         programs commit to with `RawBootInfo::abi_encode` in `crates/client-utils/src/boot.rs`.
+contract PublicValuesTest is Test {
+    bytes32 constant L1_HEAD = 0x648a03238b239b1afa20953c99812a0640fec18776b07af8b8c0cdbceacf2dc1;
+    bytes32 constant L2_PRE_ROOT = 0x5DE5BC23543212ACFE6D68CCB2F243C80CBD3765782F424DEF4B1E68BABCFE94;
+    uint constant L2_PRE_BLOCK_NUM = 15954957;
+    bytes32 constant CLAIM_ROOT = 0x48A9017ABA4E1E3FCDCF9E792FD2B749F9852FA7CF677021033E39FA2E2608EB;
+    uint constant CLAIM_BLOCK_NUM = 15955537;
+    uint constant CHAIN_ID = 11155420;
+    bytes32 constant ROLLUP_CONFIG_HASH = 0xc0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ff;
+
+    /// @dev `RawBootInfo::abi_encode` of the values above, which `crates/client-utils/tests/public_values.rs`
+    ///      checks against the Rust encoding. Regenerate both whenever the layout, and with it
+    ///      `BOOT_INFO_VERSION`, changes.
+    bytes constant RUST_ENCODING = hex"0000000000000000000000000000000000000000000000000000000000000002648a03238b239b1afa20953c99812a0640fec18776b07af8b8c0cdbceacf2dc15de5bc23543212acfe6d68ccb2f243c80cbd3765782f424def4b1e68babcfe940000000000000000000000000000000000000000000000000000000000f3740d48a9017aba4e1e3fcdcf9e792fd2b749f9852fa7cf677021033e39fa2e2608eb0000000000000000000000000000000000000000000000000000000000f376510000000000000000000000000000000000000000000000000000000000aa37dcc0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ff";
+
+    function testPublicValuesMatchRustEncoding() public {
+        ZKL2OutputOracle l2oo = new ZKL2OutputOracle();
+        ZKL2OutputOracle.PublicValuesStruct memory publicValues = ZKL2OutputOracle.PublicValuesStruct({
+            version: l2oo.PUBLIC_VALUES_VERSION(),
+            l1Head: L1_HEAD,
+            l2PreRoot: L2_PRE_ROOT,
+            l2PreBlockNum: L2_PRE_BLOCK_NUM,
+            claimRoot: CLAIM_ROOT,
+            claimBlockNum: CLAIM_BLOCK_NUM,
+            chainId: CHAIN_ID,
+            rollupConfigHash: ROLLUP_CONFIG_HASH
+        });
+
+        assertEq(abi.encode(publicValues), RUST_ENCODING);
+    }
+
+    function testPublicValuesStartWithVersion() public {
+        ZKL2OutputOracle l2oo = new ZKL2OutputOracle();
+        assertEq(uint256(bytes32(RUST_ENCODING)), l2oo.PUBLIC_VALUES_VERSION());
+        // The version word followed by the 7 fields, matching `BOOT_INFO_SIZE`.
+        assertEq(RUST_ENCODING.length, 8 * 32);
+    }
+}
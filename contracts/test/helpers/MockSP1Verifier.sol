@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// @notice A stand-in for the SP1 verifier gateway that accepts exactly one proof of one set of
+///         public values. It lets tests check the public values the oracle verifies proofs
+///         against without a real proof, which would have to be regenerated whenever the programs
+///         or the public values layout change.
+contract MockSP1Verifier {
+    bytes32 public expectedVkey;
+    bytes public expectedPublicValues;
+    bytes public expectedProof;
+
+    /// @notice Sets the program key, public values and proof that `verifyProof` accepts.
+    function expect(bytes32 _vkey, bytes memory _publicValues, bytes memory _proof) external {
+        expectedVkey = _vkey;
+        expectedPublicValues = _publicValues;
+        expectedProof = _proof;
+    }
+
+    /// @notice Reverts unless the arguments are the ones set with `expect`.
+    function verifyProof(bytes32 _vkey, bytes calldata _publicValues, bytes calldata _proof) external view {
+        require(_vkey == expectedVkey, "MockSP1Verifier: wrong vkey");
+        require(keccak256(_publicValues) == keccak256(expectedPublicValues), "MockSP1Verifier: wrong public values");
+        require(keccak256(_proof) == keccak256(expectedProof), "MockSP1Verifier: wrong proof");
+    }
+}
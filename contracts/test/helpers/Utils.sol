@@ -36,6 +36,7 @@ contract Utils is Test, JSONDecoder {
 
         ZKL2OutputOracle.ZKInitParams memory zkInitParams = ZKL2OutputOracle.ZKInitParams({
             chainId: cfg.chainId,
+            rollupConfigHash: cfg.rollupConfigHash,
             verifierGateway: cfg.verifierGateway,
             vkey: cfg.vkey,
             owner: cfg.owner,
@@ -0,0 +1,167 @@
+//! Contains a data availability source for chains that post alt-DA commitments to L1 instead of
+//! their batch data, which resolves the commitments through the preimage oracle.
+
+use alloc::{boxed::Box, format, sync::Arc};
+use alloy_primitives::{hex, Bytes, B256};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use core::fmt::Debug;
+use kona_derive::{
+    traits::{AsyncIterator, DataAvailabilityProvider},
+    types::{StageError, StageResult},
+};
+use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_primitives::BlockInfo;
+use log::warn;
+
+/// The version byte that prefixes alt-DA commitments in batcher transactions. Batcher
+/// transactions with any other version carry their frames directly.
+const ALT_DA_TX_DATA_VERSION: u8 = 0x01;
+
+/// The type byte of keccak256 commitments, whose input is the keccak256 preimage of the
+/// commitment.
+const KECCAK_COMMITMENT_TYPE: u8 = 0x00;
+
+/// The hint sent before reading the input of an alt-DA commitment. Its data is the hex encoded
+/// commitment, including the type byte, which is how the DA server is queried for the input.
+pub const ALTDA_COMMITMENT_HINT: &str = "altda-commitment";
+
+/// The data of a batcher transaction on a chain that uses alt-DA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AltDAData {
+    /// Frames posted directly to L1.
+    Frames(Bytes),
+    /// A keccak256 commitment to frames posted to the DA server.
+    Keccak(B256),
+    /// A commitment that is malformed or of a type other than keccak256. As in op-node when
+    /// running with keccak256 commitments, the transaction is skipped.
+    Unsupported(Bytes),
+}
+
+impl AltDAData {
+    /// Decodes the data of a batcher transaction. An alt-DA commitment is the version byte, the
+    /// commitment type and the commitment.
+    pub fn decode(data: Bytes) -> Self {
+        if data.first() != Some(&ALT_DA_TX_DATA_VERSION) {
+            return Self::Frames(data);
+        }
+        match (data.get(1), data.get(2..)) {
+            (Some(&KECCAK_COMMITMENT_TYPE), Some(commitment)) if commitment.len() == 32 => {
+                Self::Keccak(B256::from_slice(commitment))
+            }
+            _ => Self::Unsupported(data),
+        }
+    }
+}
+
+/// A [DataAvailabilityProvider] that wraps another and, if alt-DA is enabled, replaces the alt-DA
+/// commitments it returns with their inputs. The inputs are read from the preimage oracle under
+/// the keccak256 key of the commitment, so they are part of the witness and are verified like any
+/// other keccak256 preimage.
+///
+/// Only keccak256 commitments are supported, and other commitments are skipped, see
+/// [AltDAData::Unsupported]. The DA challenge contract is not consulted, so inputs are always
+/// expected to be available.
+///
+/// The input of each commitment is hinted with [ALTDA_COMMITMENT_HINT]. kona-host does not know
+/// the hint, so for native execution the host fetches the inputs from the DA server into its data
+/// directory beforehand.
+#[derive(Debug, Clone)]
+pub struct AltDADataSource<D, O> {
+    inner: D,
+    oracle: Arc<O>,
+    enabled: bool,
+}
+
+impl<D, O> AltDADataSource<D, O> {
+    /// Creates a new [AltDADataSource] wrapping the given source. If `enabled` is false, the data
+    /// of the wrapped source is returned as is.
+    pub fn new(inner: D, oracle: Arc<O>, enabled: bool) -> Self {
+        Self {
+            inner,
+            oracle,
+            enabled,
+        }
+    }
+}
+
+#[async_trait]
+impl<D, O> DataAvailabilityProvider for AltDADataSource<D, O>
+where
+    D: DataAvailabilityProvider + Send + Sync,
+    D::DataIter: Send,
+    O: CommsClient + Send + Sync + Debug,
+{
+    type Item = Bytes;
+    type DataIter = AltDADataIter<D::DataIter, O>;
+
+    async fn open_data(&self, block_ref: &BlockInfo) -> Result<Self::DataIter> {
+        Ok(AltDADataIter {
+            inner: self.inner.open_data(block_ref).await?,
+            oracle: self.oracle.clone(),
+            enabled: self.enabled,
+        })
+    }
+}
+
+/// The data iterator of an [AltDADataSource].
+#[derive(Debug)]
+pub struct AltDADataIter<I, O> {
+    inner: I,
+    oracle: Arc<O>,
+    enabled: bool,
+}
+
+impl<I, O: CommsClient + Send + Sync + Debug> AltDADataIter<I, O> {
+    /// Returns the input of a keccak256 commitment from the oracle.
+    async fn resolve(&self, commitment: B256) -> StageResult<Bytes> {
+        let hint = format!(
+            "{} {}",
+            ALTDA_COMMITMENT_HINT,
+            hex::encode([[KECCAK_COMMITMENT_TYPE].as_slice(), commitment.as_slice()].concat())
+        );
+        self.oracle
+            .write(&hint)
+            .await
+            .map_err(|e| StageError::Custom(anyhow!("failed to hint alt-DA input: {}", e)))?;
+
+        let input = self
+            .oracle
+            .get(PreimageKey::new(*commitment, PreimageKeyType::Keccak256))
+            .await
+            .map_err(|e| {
+                StageError::Custom(anyhow!(
+                    "alt-DA input for commitment {} is not available: {}",
+                    commitment,
+                    e
+                ))
+            })?;
+        Ok(input.into())
+    }
+}
+
+#[async_trait]
+impl<I, O> AsyncIterator for AltDADataIter<I, O>
+where
+    I: AsyncIterator + Send,
+    O: CommsClient + Send + Sync + Debug,
+{
+    type Item = Bytes;
+
+    async fn next(&mut self) -> StageResult<Self::Item> {
+        loop {
+            let data: Bytes = self.inner.next().await?.into();
+            if !self.enabled {
+                return Ok(data);
+            }
+
+            match AltDAData::decode(data) {
+                AltDAData::Frames(data) => return Ok(data),
+                AltDAData::Keccak(commitment) => return self.resolve(commitment).await,
+                AltDAData::Unsupported(data) => {
+                    warn!("Skipping unsupported alt-DA commitment: {}", data)
+                }
+            }
+        }
+    }
+}
@@ -1,44 +1,191 @@
 //! This module contains the prologue phase of the client program, pulling in the boot
 //! information, which is passed to the zkVM a public inputs to be verified on chain.
 
-use alloy_primitives::B256;
+use crate::hash_rollup_config;
+use alloy_primitives::{B256, U256};
 use alloy_sol_types::{sol, SolValue};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use kona_client::BootInfo;
 use kona_primitives::RollupConfig;
 use serde::{Deserialize, Serialize};
 
-// ABI encoding of BootInfo is 5 * 32 bytes.
-pub const BOOT_INFO_SIZE: usize = 5 * 32;
+#[cfg(not(target_os = "zkvm"))]
+use kona_preimage::{PreimageKey, PreimageKeyType, PreimageOracleClient};
+#[cfg(not(target_os = "zkvm"))]
+use std::{env, fs};
+
+/// The version of the public values layout that the programs commit to. Bump it whenever the
+/// fields of [RawBootInfoStruct] change, along with `PublicValuesStruct` in
+/// `ZKL2OutputOracle.sol`.
+pub const BOOT_INFO_VERSION: u64 = 2;
+
+// ABI encoding of BootInfo is 8 * 32 bytes: the version word followed by 7 fields.
+pub const BOOT_INFO_SIZE: usize = 8 * 32;
+
+/// The version of the layout of the inputs that the host writes to the stdin of the programs,
+/// which is written first. Bump it whenever the inputs or the serde layout of [RawBootInfo]
+/// change, so that a program fails cleanly on inputs from a mismatched host.
+pub const STDIN_VERSION: u32 = 1;
+
+/// The environment variable holding the path of a JSON rollup config to prove with, for chains
+/// that are not in kona's registry. The host passes it on to the native client.
+pub const ROLLUP_CONFIG_PATH_ENV: &str = "ROLLUP_CONFIG_PATH";
+
+// The indices of the local preimage keys that kona's `BootInfo::load` reads the boot info from.
+const L1_HEAD_KEY: u64 = 1;
+const L2_OUTPUT_ROOT_KEY: u64 = 2;
+const L2_CLAIM_KEY: u64 = 3;
+const L2_CLAIM_BLOCK_NUMBER_KEY: u64 = 4;
+const L2_CHAIN_ID_KEY: u64 = 5;
 
 /// Boot information that is committed to the zkVM as public inputs.
-/// This struct contains all information needed to generate BootInfo,
-/// as the RollupConfig can be derived from the `chain_id`.
+/// This struct contains all information needed to generate BootInfo. The RollupConfig is either
+/// supplied by the host or derived from the `chain_id`, and only its hash is committed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawBootInfo {
     /// From [`BootInfo::l1_head`].
     pub l1_head: B256,
     /// From [`BootInfo::l2_output_root`].
     pub l2_output_root: B256,
+    /// The number of the L2 block that `l2_output_root` is the output root of. The client derives
+    /// it from the output root when it starts derivation, and overwrites the value supplied by the
+    /// host with it before committing.
+    pub l2_output_root_block: u64,
     /// From [`BootInfo::l2_claim`].
     pub l2_claim: B256,
     /// From [`BootInfo::l2_claim_block`].
     pub l2_claim_block: u64,
     /// From [`BootInfo::chain_id`].
     pub chain_id: u64,
+    /// The hash of the rollup config used for the proof, see [hash_rollup_config].
+    pub rollup_config_hash: B256,
+    /// The rollup config supplied by the host, for chains that are not in kona's registry. If
+    /// not set, the rollup config is looked up in the registry by `chain_id`. Not part of the
+    /// ABI encoding, and serialized as JSON.
+    #[serde(with = "rollup_config_json")]
+    pub rollup_config: Option<RollupConfig>,
+}
+
+/// Serializes an optional rollup config as its JSON encoding, as the serde attributes of
+/// [RollupConfig] are only supported by self-describing formats and the stdin of the programs is
+/// not one.
+mod rollup_config_json {
+    use alloc::string::String;
+    use kona_primitives::RollupConfig;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        rollup_config: &Option<RollupConfig>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        rollup_config
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<RollupConfig>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Reads the rollup config at the path in [ROLLUP_CONFIG_PATH_ENV], if it is set.
+#[cfg(not(target_os = "zkvm"))]
+pub fn load_rollup_config() -> Result<Option<RollupConfig>> {
+    let Ok(path) = env::var(ROLLUP_CONFIG_PATH_ENV) else {
+        return Ok(None);
+    };
+    let rollup_config = serde_json::from_slice(&fs::read(&path)?)
+        .map_err(|e| anyhow!("invalid rollup config {}: {}", path, e))?;
+    Ok(Some(rollup_config))
+}
+
+/// Loads the boot info natively from the local preimages of the host, like kona's
+/// `BootInfo::load`, but with the rollup config at [ROLLUP_CONFIG_PATH_ENV] if it is set, so that
+/// the witness is generated with the same rollup config that is proven with.
+#[cfg(not(target_os = "zkvm"))]
+pub async fn load_boot_info<O: PreimageOracleClient + Send + Sync>(oracle: &O) -> Result<BootInfo> {
+    let l1_head: [u8; 32] = read_local(oracle, L1_HEAD_KEY).await?;
+    let l2_output_root: [u8; 32] = read_local(oracle, L2_OUTPUT_ROOT_KEY).await?;
+    let l2_claim: [u8; 32] = read_local(oracle, L2_CLAIM_KEY).await?;
+    let l2_claim_block = u64::from_be_bytes(read_local(oracle, L2_CLAIM_BLOCK_NUMBER_KEY).await?);
+    let chain_id = u64::from_be_bytes(read_local(oracle, L2_CHAIN_ID_KEY).await?);
+
+    let rollup_config = match load_rollup_config()? {
+        Some(rollup_config) => rollup_config,
+        None => RollupConfig::from_l2_chain_id(chain_id).ok_or_else(|| {
+            anyhow!(
+                "no rollup config registered for chain id {}, set {}",
+                chain_id,
+                ROLLUP_CONFIG_PATH_ENV
+            )
+        })?,
+    };
+    if rollup_config.l2_chain_id != chain_id {
+        return Err(anyhow!(
+            "rollup config is for chain id {}, not {}",
+            rollup_config.l2_chain_id,
+            chain_id
+        ));
+    }
+
+    Ok(BootInfo {
+        l1_head: l1_head.into(),
+        l2_output_root: l2_output_root.into(),
+        l2_claim: l2_claim.into(),
+        l2_claim_block,
+        chain_id,
+        rollup_config,
+    })
+}
+
+/// Reads the local preimage with the given index, which must be `N` bytes long.
+#[cfg(not(target_os = "zkvm"))]
+async fn read_local<O: PreimageOracleClient + Send + Sync, const N: usize>(
+    oracle: &O,
+    index: u64,
+) -> Result<[u8; N]> {
+    let key = PreimageKey::new(
+        *B256::left_padding_from(&index.to_be_bytes()),
+        PreimageKeyType::Local,
+    );
+    let mut value = [0u8; N];
+    oracle.get_exact(key, &mut value).await?;
+    Ok(value)
 }
 
 impl From<RawBootInfo> for BootInfo {
-    /// Convert the BootInfoWithoutRollupConfig into BootInfo by deriving the RollupConfig.
-    fn from(boot_info_without_rollup_config: RawBootInfo) -> Self {
+    /// Convert the RawBootInfo into BootInfo, using the supplied RollupConfig or deriving it from
+    /// the chain id. Panics if the RollupConfig does not match the committed hash.
+    fn from(raw_boot_info: RawBootInfo) -> Self {
+        let rollup_config = raw_boot_info
+            .resolve_rollup_config()
+            .expect("no rollup config supplied or registered for the chain id");
+        assert_eq!(
+            rollup_config.l2_chain_id, raw_boot_info.chain_id,
+            "rollup config is for a different chain"
+        );
+        assert_eq!(
+            hash_rollup_config(&rollup_config),
+            raw_boot_info.rollup_config_hash,
+            "rollup config does not match the committed hash"
+        );
+
         let RawBootInfo {
             l1_head,
             l2_output_root,
             l2_claim,
             l2_claim_block,
             chain_id,
-        } = boot_info_without_rollup_config;
-        let rollup_config = RollupConfig::from_l2_chain_id(chain_id).unwrap();
+            ..
+        } = raw_boot_info;
 
         Self {
             l1_head,
@@ -53,11 +200,14 @@ impl From<RawBootInfo> for BootInfo {
 
 sol! {
     struct RawBootInfoStruct {
+        uint64 version;
         bytes32 l1Head;
         bytes32 l2PreRoot;
+        uint64 l2PreBlockNumber;
         bytes32 l2PostRoot;
         uint64 l2BlockNumber;
         uint64 chainId;
+        bytes32 rollupConfigHash;
     }
 }
 
@@ -67,23 +217,80 @@ impl RawBootInfo {
     /// the proof.
     pub fn abi_encode(&self) -> Vec<u8> {
         RawBootInfoStruct {
+            version: BOOT_INFO_VERSION,
             l1Head: self.l1_head,
             l2PreRoot: self.l2_output_root,
+            l2PreBlockNumber: self.l2_output_root_block,
             l2PostRoot: self.l2_claim,
             l2BlockNumber: self.l2_claim_block,
             chainId: self.chain_id,
+            rollupConfigHash: self.rollup_config_hash,
         }
         .abi_encode()
     }
 
+    /// Returns the rollup config supplied by the host, or the one in kona's registry for the
+    /// chain id if none was supplied.
+    pub fn resolve_rollup_config(&self) -> Option<RollupConfig> {
+        self.rollup_config
+            .clone()
+            .or_else(|| RollupConfig::from_l2_chain_id(self.chain_id))
+    }
+
+    /// Returns the value that the local preimage key with the given index must hold for this boot
+    /// info, encoded the way kona reads it, or `None` if the index is not a boot info key.
+    ///
+    /// Only indices 1 to 5, the boot info fields that kona's client reads, have a value. Any other
+    /// local key, such as one kona may add for a new boot info field, cannot be checked against
+    /// the public values, so the oracle rejects witnesses that hold one until it is added here.
+    pub fn local_preimage(&self, index: u64) -> Option<Vec<u8>> {
+        match index {
+            L1_HEAD_KEY => Some(self.l1_head.to_vec()),
+            L2_OUTPUT_ROOT_KEY => Some(self.l2_output_root.to_vec()),
+            L2_CLAIM_KEY => Some(self.l2_claim.to_vec()),
+            L2_CLAIM_BLOCK_NUMBER_KEY => Some(self.l2_claim_block.to_be_bytes().to_vec()),
+            L2_CHAIN_ID_KEY => Some(self.chain_id.to_be_bytes().to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Decodes the boot info from the public values of a proof. Fails if the public values were
+    /// committed with a different version of the layout, or are not exactly one boot info.
     pub fn abi_decode(bytes: &[u8]) -> Result<Self> {
+        let version = public_values_version(bytes)?;
+        if version != U256::from(BOOT_INFO_VERSION) {
+            return Err(anyhow!(
+                "unsupported public values version {}, expected {}",
+                version,
+                BOOT_INFO_VERSION
+            ));
+        }
+        if bytes.len() != BOOT_INFO_SIZE {
+            return Err(anyhow!(
+                "public values are {} bytes, expected {}",
+                bytes.len(),
+                BOOT_INFO_SIZE
+            ));
+        }
+
         let boot_info = RawBootInfoStruct::abi_decode(bytes, true)?;
         Ok(Self {
             l1_head: boot_info.l1Head,
             l2_output_root: boot_info.l2PreRoot,
+            l2_output_root_block: boot_info.l2PreBlockNumber,
             l2_claim: boot_info.l2PostRoot,
             l2_claim_block: boot_info.l2BlockNumber,
             chain_id: boot_info.chainId,
+            rollup_config_hash: boot_info.rollupConfigHash,
+            rollup_config: None,
         })
     }
 }
+
+/// Returns the version word at the start of the public values of a proof.
+pub fn public_values_version(bytes: &[u8]) -> Result<U256> {
+    let word = bytes
+        .get(..32)
+        .ok_or_else(|| anyhow!("public values are too short to hold a version"))?;
+    Ok(U256::from_be_slice(word))
+}
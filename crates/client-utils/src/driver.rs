@@ -3,11 +3,11 @@
 //!
 //! [L2PayloadAttributes]: kona_derive::types::L2PayloadAttributes
 
-use crate::l2_chain_provider::MultiblockOracleL2ChainProvider;
+use crate::{altda::AltDADataSource, l2_chain_provider::MultiblockOracleL2ChainProvider};
 use alloc::sync::Arc;
 use alloy_consensus::{Header, Sealed};
 use anyhow::{anyhow, Result};
-use core::fmt::Debug;
+use core::fmt::{self, Debug};
 use kona_client::{
     l1::{OracleBlobProvider, OracleL1ChainProvider},
     BootInfo, HintType,
@@ -19,23 +19,142 @@ use kona_derive::{
         AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue, L1Retrieval,
         L1Traversal, StatefulAttributesBuilder,
     },
-    traits::{ChainProvider, L2ChainProvider},
+    traits::{ChainProvider, L2ChainProvider, OriginProvider},
     types::StageError,
 };
 use kona_mpt::TrieDBFetcher;
 use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
-use kona_primitives::{BlockInfo, L2AttributesWithParent, L2BlockInfo};
+use kona_primitives::{BlockInfo, L2AttributesWithParent, L2BlockInfo, L2PayloadAttributes};
 use log::{debug, error};
 
+/// The EIP-2718 type byte of deposit transactions.
+pub(crate) const DEPOSIT_TX_TYPE: u8 = 0x7E;
+
+/// Returns a copy of the attributes that only includes their deposit transactions. Per the OP
+/// Stack derivation spec, a payload that fails to execute is replaced with this deposit-only
+/// payload, like op-node does.
+pub fn deposit_only_attributes(attributes: &L2PayloadAttributes) -> L2PayloadAttributes {
+    let mut deposit_only = attributes.clone();
+    deposit_only
+        .transactions
+        .retain(|raw_tx| raw_tx.first() == Some(&DEPOSIT_TX_TYPE));
+    deposit_only
+}
+
+/// Returns the deposit-only attributes that replace a payload whose execution failed with
+/// `error`, or the error if the payload is not known to be invalid.
+///
+/// The execution error does not tell an invalid block apart from missing witness data, so any
+/// failure during which a witness fetch of the L2 chain provider failed, given the number of
+/// failed fetches from before the execution, is returned as is. Otherwise the host could withhold
+/// witness data to force a deposit-only block.
+pub fn deposit_only_fallback<O: CommsClient>(
+    l2_chain_provider: &MultiblockOracleL2ChainProvider<O>,
+    failed_fetches_before: u64,
+    attributes: &L2PayloadAttributes,
+    error: anyhow::Error,
+) -> Result<L2PayloadAttributes> {
+    if l2_chain_provider.failed_fetches() != failed_fetches_before {
+        return Err(error.context("payload execution is missing witness data"));
+    }
+    let deposit_only = deposit_only_attributes(attributes);
+    if deposit_only.transactions.len() == attributes.transactions.len() {
+        return Err(error.context("deposit-only payload failed to execute"));
+    }
+    Ok(deposit_only)
+}
+
+/// The default number of times [MultiBlockDerivationDriver::produce_payloads] may step on the
+/// pipeline before giving up on deriving the claimed range.
+pub const DEFAULT_MAX_STEPS: u64 = 1 << 20;
+
+/// The number of consecutive failed steps, without advancing the origin or preparing attributes in
+/// between, after which a stage is considered stuck.
+const MAX_CONSECUTIVE_STEP_FAILURES: u64 = 64;
+
+/// The reason the [MultiBlockDerivationDriver] could not derive the claimed range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivationError {
+    /// The pipeline's L1 origin reached the L1 head of the boot info before the claim block was
+    /// derived, so the L1 head is too early for the claimed range.
+    L1HeadExhausted {
+        /// The number of the L1 head.
+        l1_head: u64,
+        /// The number of the last L2 block derived.
+        l2_safe_head: u64,
+    },
+    /// The pipeline could not advance its L1 origin before reaching the L1 head, because L1 data
+    /// it needs is missing.
+    MissingData {
+        /// The number of the L1 origin the pipeline could not advance from.
+        origin: u64,
+        /// The error returned by the pipeline.
+        reason: String,
+    },
+    /// A stage kept failing for a reason other than needing more data, such as an invalid batch.
+    StepFailed {
+        /// The number of the last L2 block derived.
+        l2_safe_head: u64,
+        /// The last error returned by the pipeline.
+        reason: String,
+    },
+    /// The pipeline was stepped the maximum number of times without deriving the claim block.
+    StepBudgetExhausted {
+        /// The number of steps taken.
+        steps: u64,
+        /// The number of the last L2 block derived.
+        l2_safe_head: u64,
+    },
+}
+
+impl fmt::Display for DerivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::L1HeadExhausted {
+                l1_head,
+                l2_safe_head,
+            } => write!(
+                f,
+                "reached l1 head {} after deriving l2 block {}, the l1 head is too early for the claim",
+                l1_head, l2_safe_head
+            ),
+            Self::MissingData { origin, reason } => write!(
+                f,
+                "failed to advance the l1 origin past block {}: {}",
+                origin, reason
+            ),
+            Self::StepFailed {
+                l2_safe_head,
+                reason,
+            } => write!(
+                f,
+                "derivation is stuck after l2 block {}: {}",
+                l2_safe_head, reason
+            ),
+            Self::StepBudgetExhausted {
+                steps,
+                l2_safe_head,
+            } => write!(
+                f,
+                "stepped {} times without deriving the claim, last derived l2 block {}",
+                steps, l2_safe_head
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DerivationError {}
+
 /// An oracle-backed derivation pipeline.
 pub type OraclePipeline<O> = DerivationPipeline<
     OracleAttributesQueue<OracleDataProvider<O>, O>,
     MultiblockOracleL2ChainProvider<O>,
 >;
 
-/// An oracle-backed Ethereum data source.
+/// An oracle-backed Ethereum data source, which resolves alt-DA commitments through the oracle
+/// on chains that use alt-DA.
 pub type OracleDataProvider<O> =
-    EthereumDataSource<OracleL1ChainProvider<O>, OracleBlobProvider<O>>;
+    AltDADataSource<EthereumDataSource<OracleL1ChainProvider<O>, OracleBlobProvider<O>>, O>;
 
 /// An oracle-backed payload attributes builder for the `AttributesQueue` stage of the derivation
 /// pipeline.
@@ -70,6 +189,19 @@ pub struct MultiBlockDerivationDriver<O: CommsClient + Send + Sync + Debug> {
     pub pipeline: OraclePipeline<O>,
     /// The block number of the final L2 block being claimed.
     pub l2_claim_block: u64,
+    /// The block number of the L2 block that the starting output root belongs to, as derived by
+    /// [MultiBlockDerivationDriver::find_startup_info].
+    pub l2_output_root_block: u64,
+    /// The block number of the L1 head of the boot info.
+    pub l1_head_number: u64,
+    /// The maximum number of times the pipeline may be stepped.
+    pub max_steps: u64,
+    /// The number of times the pipeline has been stepped.
+    steps: u64,
+    /// The number of times the pipeline has advanced its L1 origin.
+    origin_advances: u64,
+    /// The number of consecutive failed steps.
+    consecutive_failures: u64,
 }
 
 impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
@@ -82,7 +214,8 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
     /// providers.
     ///
     /// ## Takes
-    /// - `cfg`: The rollup configuration.
+    /// - `boot_info`: The boot information.
+    /// - `caching_oracle`: The caching oracle, which also serves alt-DA inputs.
     /// - `blob_provider`: The blob provider.
     /// - `chain_provider`: The L1 chain provider.
     /// - `l2_chain_provider`: The L2 chain provider.
@@ -91,7 +224,7 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
     /// - A new [MultiBlockDerivationDriver] instance.
     pub async fn new(
         boot_info: &BootInfo,
-        caching_oracle: &O,
+        caching_oracle: Arc<O>,
         blob_provider: OracleBlobProvider<O>,
         mut chain_provider: OracleL1ChainProvider<O>,
         mut l2_chain_provider: MultiblockOracleL2ChainProvider<O>,
@@ -100,12 +233,16 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
 
         // Fetch the startup information.
         let (l1_origin, l2_safe_head, l2_safe_head_header) = Self::find_startup_info(
-            caching_oracle,
+            caching_oracle.as_ref(),
             boot_info,
             &mut chain_provider,
             &mut l2_chain_provider,
         )
         .await?;
+        let l1_head_number = chain_provider
+            .header_by_hash(boot_info.l1_head)
+            .await?
+            .number;
 
         // Construct the pipeline.
         let attributes = StatefulAttributesBuilder::new(
@@ -113,7 +250,12 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
             l2_chain_provider.clone(),
             chain_provider.clone(),
         );
-        let dap = EthereumDataSource::new(chain_provider.clone(), blob_provider, &cfg);
+        // Chains with a DA challenge contract post alt-DA commitments rather than batch data.
+        let dap = AltDADataSource::new(
+            EthereumDataSource::new(chain_provider.clone(), blob_provider, &cfg),
+            caching_oracle,
+            cfg.da_challenge_address.is_some(),
+        );
         let pipeline = PipelineBuilder::new()
             .rollup_config(cfg)
             .dap_source(dap)
@@ -124,14 +266,38 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
             .build();
 
         let l2_claim_block = boot_info.l2_claim_block;
+        let l2_output_root_block = l2_safe_head.block_info.number;
         Ok(Self {
             l2_safe_head,
             l2_safe_head_header,
             pipeline,
             l2_claim_block,
+            l2_output_root_block,
+            l1_head_number,
+            max_steps: DEFAULT_MAX_STEPS,
+            steps: 0,
+            origin_advances: 0,
+            consecutive_failures: 0,
         })
     }
 
+    /// Returns the number of times the pipeline has been stepped.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Returns the number of times the pipeline has advanced its L1 origin.
+    pub fn origin_advances(&self) -> u64 {
+        self.origin_advances
+    }
+
+    /// Sets the maximum number of times the pipeline may be stepped before
+    /// [MultiBlockDerivationDriver::produce_payloads] gives up.
+    pub fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
     pub fn update_safe_head(
         &mut self,
         new_safe_head: L2BlockInfo,
@@ -143,14 +309,27 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
 
     /// Produces the disputed [Vec<L2AttributesWithParent>] payloads, starting with the one after
     /// the L2 output root, for all the payloads derived in a given span batch.
-    pub async fn produce_payloads(&mut self) -> Result<Vec<L2AttributesWithParent>> {
-        debug!(
-            "Stepping on Pipeline for L2 Block: {}",
-            self.l2_safe_head.block_info.number
-        );
+    ///
+    /// Returns an empty vec if the step made progress without preparing attributes, and an error
+    /// if derivation cannot complete: the L1 origin cannot advance, a stage is stuck, or the step
+    /// budget is used up.
+    pub async fn produce_payloads(
+        &mut self,
+    ) -> Result<Vec<L2AttributesWithParent>, DerivationError> {
+        let l2_safe_head = self.l2_safe_head.block_info.number;
+        if self.steps >= self.max_steps {
+            return Err(DerivationError::StepBudgetExhausted {
+                steps: self.steps,
+                l2_safe_head,
+            });
+        }
+        self.steps += 1;
+
+        debug!("Stepping on Pipeline for L2 Block: {}", l2_safe_head);
         match self.pipeline.step(self.l2_safe_head).await {
             StepResult::PreparedAttributes => {
                 debug!("Found Attributes");
+                self.consecutive_failures = 0;
                 let mut payloads = Vec::new();
                 for attr in self.pipeline.by_ref() {
                     let parent_block_nb = attr.parent.block_info.number;
@@ -163,18 +342,42 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
             }
             StepResult::AdvancedOrigin => {
                 debug!("Advanced Origin");
+                self.origin_advances += 1;
+                self.consecutive_failures = 0;
             }
+            // The oracle cannot serve L1 data past the L1 head, and any other data it is missing
+            // will not appear on a retry, so the origin can never advance.
             StepResult::OriginAdvanceErr(e) => {
                 error!("Origin Advance Error: {:?}", e);
-            }
-            StepResult::StepFailed(e) => match e {
-                StageError::NotEnoughData => {
-                    debug!("Failed: Not Enough Data");
+                let origin = self
+                    .pipeline
+                    .origin()
+                    .map(|origin| origin.number)
+                    .unwrap_or_default();
+                if origin >= self.l1_head_number {
+                    return Err(DerivationError::L1HeadExhausted {
+                        l1_head: self.l1_head_number,
+                        l2_safe_head,
+                    });
                 }
-                _ => {
-                    error!("Failed: {:?}", e);
+                return Err(DerivationError::MissingData {
+                    origin,
+                    reason: format!("{:?}", e),
+                });
+            }
+            StepResult::StepFailed(StageError::NotEnoughData) => {
+                debug!("Failed: Not Enough Data");
+            }
+            StepResult::StepFailed(e) => {
+                error!("Failed: {:?}", e);
+                self.consecutive_failures += 1;
+                if self.consecutive_failures >= MAX_CONSECUTIVE_STEP_FAILURES {
+                    return Err(DerivationError::StepFailed {
+                        l2_safe_head,
+                        reason: format!("{:?}", e),
+                    });
                 }
-            },
+            }
         }
 
         Ok(Vec::new())
@@ -189,7 +392,8 @@ impl<O: CommsClient + Send + Sync + Debug> MultiBlockDerivationDriver<O> {
     /// - `l2_chain_provider`: The L2 chain provider.
     ///
     /// ## Returns
-    /// - A tuple containing the L1 origin block information and the L2 safe head information.
+    /// - A tuple containing the L1 origin block information, the L2 safe head information, whose
+    ///   number is the L2 block number of the starting output root, and the L2 safe head header.
     async fn find_startup_info(
         caching_oracle: &O,
         boot_info: &BootInfo,
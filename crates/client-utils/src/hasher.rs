@@ -10,11 +10,13 @@ pub struct BytesHasher {
 
 impl Hasher for BytesHasher {
     /// Takes the first 8 bytes of the PreimageKey (which includes the type)
-    /// and then converts them in little-endian order to a u64.
+    /// and then converts them in big-endian order to a u64.
+    /// Note: [OracleKey](crate::OracleKey) writes its raw bytes in a single call, without a
+    /// length prefix. Writes shorter than 8 bytes, such as the length prefix of a slice on the
+    /// 32-bit zkVM, are skipped.
     fn write(&mut self, bytes: &[u8]) {
-        // Note: write() will be called with the length first, but this check will skip it.
-        if bytes.len() == 32 {
-            self.hash = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        if let Some(prefix) = bytes.first_chunk::<8>() {
+            self.hash = u64::from_be_bytes(*prefix);
         }
     }
 
@@ -0,0 +1,17 @@
+//! Contains the KZG trusted setup, which is shared by the blob verification of the oracle and the
+//! point evaluation precompile.
+
+use kzg_rs::{KzgError, KzgSettings};
+use std::sync::OnceLock;
+
+/// Returns the KZG trusted setup, loading it on the first call. Loading it is expensive inside
+/// the zkVM, so it is loaded at most once per run, and only if a blob or point evaluation is
+/// verified.
+pub(crate) fn kzg_settings() -> Result<&'static KzgSettings, KzgError> {
+    static KZG_SETTINGS: OnceLock<KzgSettings> = OnceLock::new();
+    if let Some(kzg_settings) = KZG_SETTINGS.get() {
+        return Ok(kzg_settings);
+    }
+    let kzg_settings = KzgSettings::load_trusted_setup_file()?;
+    Ok(KZG_SETTINGS.get_or_init(|| kzg_settings))
+}
@@ -1,6 +1,6 @@
 //! Contains the concrete implementation of the [L2ChainProvider] trait for the client program.
 
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
 use alloy_consensus::Header;
 use alloy_eips::eip2718::Decodable2718;
 use alloy_primitives::{Address, Bytes, B256};
@@ -15,10 +15,131 @@ use kona_primitives::{
     L2BlockInfo, L2ExecutionPayloadEnvelope, OpBlock, RollupConfig, SystemConfig,
 };
 use op_alloy_consensus::OpTxEnvelope;
-use std::{collections::HashMap, sync::Mutex};
+use std::sync::Mutex;
 
 use crate::block_on;
 
+/// The L1 block time in seconds, used to convert the L1 block windows of the rollup config into
+/// L2 blocks.
+const L1_BLOCK_TIME: u64 = 12;
+
+/// A cell shared between the clones of the provider, of which the derivation pipeline holds
+/// several.
+#[derive(Debug)]
+struct SharedCell<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+impl<T> SharedCell<T> {
+    fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(value)),
+        }
+    }
+
+    /// Runs `f` with mutable access to the value. `f` must not access the cell itself.
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.inner.lock().unwrap())
+    }
+}
+
+impl<T> Clone for SharedCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// The data of an executed L2 block, which is trusted once the block has been executed.
+#[derive(Debug)]
+struct CachedBlock {
+    header: Header,
+    l2_block_info: L2BlockInfo,
+    system_config: SystemConfig,
+    /// Shared so that a cache hit only holds the lock for a reference count increment. The
+    /// payload is copied out of it after the lock is released, as the pipeline takes it by value.
+    payload: Arc<L2ExecutionPayloadEnvelope>,
+}
+
+/// The executed L2 blocks that the derivation pipeline can still reference, by block number, and
+/// the headers that have been walked back to.
+#[derive(Debug)]
+struct BlockCache {
+    blocks: BTreeMap<u64, CachedBlock>,
+    /// Headers walked back from the starting header or an executed block, by block number. They
+    /// are trusted as they are linked by hash to a trusted header. Walks usually end below the
+    /// window of executed blocks, so these are bounded separately, by count.
+    headers: BTreeMap<u64, Header>,
+    /// The header of the L2 safe head at the start of the range, once read from the starting
+    /// output root.
+    starting_header: Option<Header>,
+    /// The number of blocks kept behind the newest block.
+    window: u64,
+}
+
+impl BlockCache {
+    /// Creates a cache that keeps the blocks within the longer of the sequencing window and the
+    /// channel timeout behind the newest block. Batches that are still being derived cannot
+    /// reference older blocks, so those are only looked up again if the pipeline resets, in which
+    /// case they are read from the oracle.
+    fn new(config: &RollupConfig) -> Self {
+        let l1_blocks = config.seq_window_size.max(config.channel_timeout);
+        Self {
+            blocks: BTreeMap::new(),
+            headers: BTreeMap::new(),
+            starting_header: None,
+            window: l1_blocks * L1_BLOCK_TIME / config.block_time.max(1),
+        }
+    }
+
+    /// Inserts an executed block and evicts the blocks that have fallen out of the window.
+    fn insert(&mut self, number: u64, block: CachedBlock) {
+        self.blocks.insert(number, block);
+        let oldest = number.saturating_sub(self.window);
+        if self
+            .blocks
+            .first_key_value()
+            .is_some_and(|(first, _)| *first < oldest)
+        {
+            self.blocks = self.blocks.split_off(&oldest);
+        }
+    }
+
+    /// Inserts a walked header. At most as many headers are kept as blocks fit in the window, and
+    /// the lowest ones are evicted first, so the headers closest to the executed blocks remain to
+    /// walk back from.
+    fn insert_header(&mut self, header: Header) {
+        self.headers.insert(header.number, header);
+        if self.headers.len() as u64 > self.window.max(1) {
+            self.headers.pop_first();
+        }
+    }
+
+    /// Returns the known header with the lowest block number at or after the given one, which is
+    /// the closest header to walk back from.
+    fn nearest_header(&self, number: u64) -> Option<&Header> {
+        let executed = self
+            .blocks
+            .range(number..)
+            .next()
+            .map(|(_, block)| &block.header);
+        let walked = self
+            .headers
+            .range(number..)
+            .next()
+            .map(|(_, header)| header);
+        let starting = self
+            .starting_header
+            .as_ref()
+            .filter(|header| header.number >= number);
+        [executed, walked, starting]
+            .into_iter()
+            .flatten()
+            .min_by_key(|header| header.number)
+    }
+}
+
 /// The oracle-backed L2 chain provider for the client program.
 #[derive(Debug, Clone)]
 pub struct MultiblockOracleL2ChainProvider<T: CommsClient> {
@@ -26,28 +147,39 @@ pub struct MultiblockOracleL2ChainProvider<T: CommsClient> {
     boot_info: Arc<BootInfo>,
     /// The preimage oracle client.
     oracle: Arc<T>,
-    /// Cached headers by block number.
-    header_by_number: Arc<Mutex<HashMap<u64, Header>>>,
-    /// Cached L2 block info by block number.
-    l2_block_info_by_number: Arc<Mutex<HashMap<u64, L2BlockInfo>>>,
-    /// Cached payloads by block number.
-    payload_by_number: Arc<Mutex<HashMap<u64, L2ExecutionPayloadEnvelope>>>,
-    /// Cached system configs by block number.
-    system_config_by_number: Arc<Mutex<HashMap<u64, SystemConfig>>>,
+    /// Cached data of the executed blocks, shared between the clones of the provider.
+    cache: SharedCell<BlockCache>,
+    /// The number of witness fetches through [TrieDBFetcher] and [TrieDBHinter] that have failed,
+    /// shared between the clones of the provider.
+    failed_fetches: SharedCell<u64>,
 }
 
 impl<T: CommsClient> MultiblockOracleL2ChainProvider<T> {
     /// Creates a new [MultiblockOracleL2ChainProvider] with the given boot information and oracle client.
     pub fn new(boot_info: Arc<BootInfo>, oracle: Arc<T>) -> Self {
+        let cache = SharedCell::new(BlockCache::new(&boot_info.rollup_config));
         Self {
             boot_info,
             oracle,
-            header_by_number: Arc::new(Mutex::new(HashMap::new())),
-            l2_block_info_by_number: Arc::new(Mutex::new(HashMap::new())),
-            payload_by_number: Arc::new(Mutex::new(HashMap::new())),
-            system_config_by_number: Arc::new(Mutex::new(HashMap::new())),
+            cache,
+            failed_fetches: SharedCell::new(0),
         }
     }
+
+    /// Returns the number of witness fetches by the executor that have failed so far. A payload
+    /// whose execution failed while this number increased is missing witness data, rather than
+    /// being invalid.
+    pub fn failed_fetches(&self) -> u64 {
+        self.failed_fetches.with(|failed| *failed)
+    }
+
+    /// Counts the fetch as failed if the result is an error.
+    fn record_fetch<R>(&self, result: Result<R>) -> Result<R> {
+        if result.is_err() {
+            self.failed_fetches.with(|failed| *failed += 1);
+        }
+        result
+    }
 }
 
 impl<T: CommsClient> MultiblockOracleL2ChainProvider<T> {
@@ -58,33 +190,72 @@ impl<T: CommsClient> MultiblockOracleL2ChainProvider<T> {
         payload: L2ExecutionPayloadEnvelope,
         config: &RollupConfig,
     ) -> Result<L2BlockInfo> {
-        self.header_by_number
-            .lock()
-            .unwrap()
-            .insert(header.number, header.clone());
-        self.payload_by_number
-            .lock()
-            .unwrap()
-            .insert(header.number, payload.clone());
-        self.system_config_by_number
-            .lock()
-            .unwrap()
-            .insert(header.number, payload.to_system_config(config).unwrap());
-
-        let l2_block_info = payload.to_l2_block_ref(config)?;
-        self.l2_block_info_by_number
-            .lock()
-            .unwrap()
-            .insert(header.number, l2_block_info);
-        Ok(l2_block_info)
+        println!("cycle-tracker-report-start: l2-cache-update");
+        let result = Self::cached_block(header, payload, config).map(|block| {
+            let l2_block_info = block.l2_block_info;
+            self.cache.with(|cache| cache.insert(header.number, block));
+            l2_block_info
+        });
+        println!("cycle-tracker-report-end: l2-cache-update");
+        result
+    }
+
+    /// Returns the data of the executed block to cache.
+    fn cached_block(
+        header: &Header,
+        payload: L2ExecutionPayloadEnvelope,
+        config: &RollupConfig,
+    ) -> Result<CachedBlock> {
+        Ok(CachedBlock {
+            header: header.clone(),
+            l2_block_info: payload.to_l2_block_ref(config)?,
+            system_config: payload.to_system_config(config)?,
+            payload: Arc::new(payload),
+        })
     }
 
     /// Returns a [Header] corresponding to the given L2 block number, by walking back from the
-    /// L2 safe head.
+    /// nearest known header after it. The headers walked past are cached, so later lookups of
+    /// nearby blocks only walk the remaining distance.
     pub async fn header_by_number(&mut self, block_number: u64) -> Result<Header> {
-        // First, check if it's already in the cache.
-        if let Some(header) = self.header_by_number.lock().unwrap().get(&block_number) {
-            return Ok(header.clone());
+        // First, check if it's already in the cache, or find the closest header to walk back from.
+        let nearest = self
+            .cache
+            .with(|cache| cache.nearest_header(block_number).cloned());
+        let header = match nearest {
+            Some(header) => header,
+            None => {
+                let header = self.starting_header().await?;
+                // Check if the block number is in range. If not, we can fail early.
+                if block_number > header.number {
+                    anyhow::bail!("Block number past L2 head.");
+                }
+                header
+            }
+        };
+
+        // Walk back the block headers to the desired block number.
+        println!("cycle-tracker-report-start: l2-header-walk-back");
+        let result = self.walk_back(header, block_number);
+        println!("cycle-tracker-report-end: l2-header-walk-back");
+        result
+    }
+
+    /// Walks back from `header` to the header of the given block number, caching the headers
+    /// walked past.
+    fn walk_back(&self, mut header: Header, block_number: u64) -> Result<Header> {
+        while header.number > block_number {
+            header = self.header_by_hash(header.parent_hash)?;
+            self.cache.with(|cache| cache.insert_header(header.clone()));
+        }
+        Ok(header)
+    }
+
+    /// Returns the header of the L2 safe head at the start of the range, which is read from the
+    /// starting output root once per provider.
+    async fn starting_header(&self) -> Result<Header> {
+        if let Some(header) = self.cache.with(|cache| cache.starting_header.clone()) {
+            return Ok(header);
         }
 
         // Fetch the starting L2 output preimage.
@@ -105,18 +276,10 @@ impl<T: CommsClient> MultiblockOracleL2ChainProvider<T> {
         let block_hash = output_preimage[96..128]
             .try_into()
             .map_err(|e| anyhow!("Failed to extract block hash from output preimage: {e}"))?;
-        let mut header = self.header_by_hash(block_hash)?;
-
-        // Check if the block number is in range. If not, we can fail early.
-        if block_number > header.number {
-            anyhow::bail!("Block number past L2 head.");
-        }
-
-        // Walk back the block headers to the desired block number.
-        while header.number > block_number {
-            header = self.header_by_hash(header.parent_hash)?;
-        }
+        let header = self.header_by_hash(block_hash)?;
 
+        self.cache
+            .with(|cache| cache.starting_header = Some(header.clone()));
         Ok(header)
     }
 }
@@ -125,8 +288,11 @@ impl<T: CommsClient> MultiblockOracleL2ChainProvider<T> {
 impl<T: CommsClient + Send + Sync> L2ChainProvider for MultiblockOracleL2ChainProvider<T> {
     async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo> {
         // First, check if it's already in the cache.
-        if let Some(l2_block_info) = self.l2_block_info_by_number.lock().unwrap().get(&number) {
-            return Ok(*l2_block_info);
+        if let Some(l2_block_info) = self
+            .cache
+            .with(|cache| cache.blocks.get(&number).map(|block| block.l2_block_info))
+        {
+            return Ok(l2_block_info);
         }
 
         // Get the payload at the given block number.
@@ -138,8 +304,11 @@ impl<T: CommsClient + Send + Sync> L2ChainProvider for MultiblockOracleL2ChainPr
 
     async fn payload_by_number(&mut self, number: u64) -> Result<L2ExecutionPayloadEnvelope> {
         // First, check if it's already in the cache.
-        if let Some(payload) = self.payload_by_number.lock().unwrap().get(&number) {
-            return Ok(payload.clone());
+        if let Some(payload) = self
+            .cache
+            .with(|cache| cache.blocks.get(&number).map(|block| block.payload.clone()))
+        {
+            return Ok(L2ExecutionPayloadEnvelope::clone(&payload));
         }
 
         // Fetch the header for the given block number.
@@ -184,8 +353,13 @@ impl<T: CommsClient + Send + Sync> L2ChainProvider for MultiblockOracleL2ChainPr
         rollup_config: Arc<RollupConfig>,
     ) -> Result<SystemConfig> {
         // First, check if it's already in the cache.
-        if let Some(system_config) = self.system_config_by_number.lock().unwrap().get(&number) {
-            return Ok(system_config.clone());
+        if let Some(system_config) = self.cache.with(|cache| {
+            cache
+                .blocks
+                .get(&number)
+                .map(|block| block.system_config.clone())
+        }) {
+            return Ok(system_config);
         }
 
         // Get the payload at the given block number.
@@ -200,17 +374,17 @@ impl<T: CommsClient> TrieDBFetcher for MultiblockOracleL2ChainProvider<T> {
     fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
         // On L2, trie node preimages are stored as keccak preimage types in the oracle. We assume
         // that a hint for these preimages has already been sent, prior to this call.
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
                 .await
                 .map(Into::into)
-        })
+        }))
     }
 
     fn bytecode_by_hash(&self, hash: B256) -> Result<Bytes> {
         // Fetch the bytecode preimage from the caching oracle.
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .write(&HintType::L2Code.encode_with(&[hash.as_ref()]))
                 .await?;
@@ -219,12 +393,12 @@ impl<T: CommsClient> TrieDBFetcher for MultiblockOracleL2ChainProvider<T> {
                 .get(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
                 .await
                 .map(Into::into)
-        })
+        }))
     }
 
     fn header_by_hash(&self, hash: B256) -> Result<Header> {
         // Fetch the header from the caching oracle.
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .write(&HintType::L2BlockHeader.encode_with(&[hash.as_ref()]))
                 .await?;
@@ -235,28 +409,28 @@ impl<T: CommsClient> TrieDBFetcher for MultiblockOracleL2ChainProvider<T> {
                 .await?;
             Header::decode(&mut header_bytes.as_slice())
                 .map_err(|e| anyhow!("Failed to RLP decode Header: {e}"))
-        })
+        }))
     }
 }
 
 impl<T: CommsClient> TrieDBHinter for MultiblockOracleL2ChainProvider<T> {
     fn hint_trie_node(&self, hash: B256) -> Result<()> {
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .write(&HintType::L2StateNode.encode_with(&[hash.as_slice()]))
                 .await
-        })
+        }))
     }
 
     fn hint_account_proof(&self, address: Address, block_number: u64) -> Result<()> {
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .write(
                     &HintType::L2AccountProof
                         .encode_with(&[block_number.to_be_bytes().as_ref(), address.as_slice()]),
                 )
                 .await
-        })
+        }))
     }
 
     fn hint_storage_proof(
@@ -265,7 +439,7 @@ impl<T: CommsClient> TrieDBHinter for MultiblockOracleL2ChainProvider<T> {
         slot: alloy_primitives::U256,
         block_number: u64,
     ) -> Result<()> {
-        block_on(async move {
+        self.record_fetch(block_on(async move {
             self.oracle
                 .write(&HintType::L2AccountStorageProof.encode_with(&[
                     block_number.to_be_bytes().as_ref(),
@@ -273,6 +447,6 @@ impl<T: CommsClient> TrieDBHinter for MultiblockOracleL2ChainProvider<T> {
                     slot.to_be_bytes::<32>().as_ref(),
                 ]))
                 .await
-        })
+        }))
     }
 }
@@ -2,13 +2,28 @@ mod hasher;
 pub use hasher::BytesHasherBuilder;
 
 mod boot;
-pub use boot::{RawBootInfo, BOOT_INFO_SIZE};
+#[cfg(not(target_os = "zkvm"))]
+pub use boot::{load_boot_info, load_rollup_config};
+pub use boot::{
+    public_values_version, RawBootInfo, BOOT_INFO_SIZE, BOOT_INFO_VERSION, ROLLUP_CONFIG_PATH_ENV,
+    STDIN_VERSION,
+};
+
+mod rollup_config;
+pub use rollup_config::{hash_rollup_config, ROLLUP_CONFIG_HASH_VERSION};
 
 mod executor;
 pub use executor::block_on;
 
+mod kzg;
+
 mod oracle;
-pub use oracle::InMemoryOracle;
+pub use oracle::{
+    decode_lazy_verification_counts, ArchivedInMemoryOracle, InMemoryOracle, InvalidOracleKey,
+    KvStore, OracleKey, OracleVerifyError, WitnessEncoding, LAZY_VERIFICATION_HOOK_FD,
+};
+#[cfg(not(target_os = "zkvm"))]
+pub use oracle::{AccessTrackingOracle, ACCESS_LOG_ENV};
 
 pub mod precompiles;
 
@@ -16,5 +31,8 @@ pub mod types;
 
 extern crate alloc;
 
+pub mod altda;
 pub mod driver;
 pub mod l2_chain_provider;
+#[cfg(not(target_os = "zkvm"))]
+pub mod trace;
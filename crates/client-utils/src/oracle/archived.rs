@@ -0,0 +1,172 @@
+//! Contains an oracle that serves preimages directly from the archived bytes passed into the
+//! zkVM, without deserializing them.
+
+use super::{
+    verify_preimages, KvStore, LazyVerifier, OracleKey, OracleVerifyError, PreimageStore,
+    WitnessEncoding,
+};
+use crate::RawBootInfo;
+use alloy_primitives::hex;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use core::fmt;
+use kona_preimage::{HintWriterClient, PreimageKey, PreimageOracleClient};
+use rkyv::{collections::ArchivedHashMap, vec::ArchivedVec, AlignedVec, Archived};
+
+/// The file descriptor of the hook through which the guest reports the counts of
+/// [ArchivedInMemoryOracle::lazy_verification_counts] to the host, which adds them to its
+/// execution stats.
+pub const LAZY_VERIFICATION_HOOK_FD: u32 = 116;
+
+/// Decodes the counts the guest reports at [LAZY_VERIFICATION_HOOK_FD]: the number of preimages
+/// that were verified and the number that were never read, as little-endian `u64`s.
+pub fn decode_lazy_verification_counts(buf: &[u8]) -> Option<(u64, u64)> {
+    let (verified, unused) = buf.split_first_chunk::<8>()?;
+    Some((
+        u64::from_le_bytes(*verified),
+        u64::from_le_bytes(unused.try_into().ok()?),
+    ))
+}
+
+/// The archived form of the [KvStore].
+type ArchivedKvStore = ArchivedHashMap<OracleKey, ArchivedVec<u8>>;
+
+/// An oracle backed by the rkyv archive of the key value store. The preimages are never copied
+/// out of the archive, which saves both the cycles and the memory of deserializing the full store.
+///
+/// The archive is validated with `check_bytes` on creation, which also rejects keys without a
+/// valid key type, and the underlying bytes are leaked so they live for the remainder of
+/// execution.
+///
+/// The preimages are either verified up front with [ArchivedInMemoryOracle::verify], or, if the
+/// oracle is created with [ArchivedInMemoryOracle::with_lazy_verification], the first time they
+/// are read.
+pub struct ArchivedInMemoryOracle {
+    cache: &'static ArchivedKvStore,
+    lazy: Option<LazyVerifier>,
+}
+
+impl ArchivedInMemoryOracle {
+    /// Creates a new [ArchivedInMemoryOracle] from the raw bytes passed into the zkVM, decoding
+    /// them with the given encoding first. Fails if the bytes are not a valid archive of the key
+    /// value store.
+    pub fn from_raw_bytes(input: Vec<u8>, encoding: WitnessEncoding) -> Result<Self> {
+        let input = encoding.decode(input)?;
+
+        // The archive must be aligned for rkyv to read it in place. Only copy the bytes if the
+        // allocation we received is not already aligned.
+        let bytes: &'static [u8] = if input.as_ptr() as usize % AlignedVec::ALIGNMENT == 0 {
+            input.leak()
+        } else {
+            let mut aligned = AlignedVec::with_capacity(input.len());
+            aligned.extend_from_slice(&input);
+            Box::leak(Box::new(aligned)).as_slice()
+        };
+
+        let cache: &'static Archived<KvStore> = rkyv::check_archived_root::<KvStore>(bytes)
+            .map_err(|e| anyhow!("invalid key value store archive: {}", e))?;
+
+        Ok(Self { cache, lazy: None })
+    }
+
+    /// Switches the oracle to verifying each preimage the first time it is read, rather than
+    /// verifying all preimages up front. Preimages that are never read are never verified, and
+    /// a blob is verified as a whole on the first read of any of its elements. Local preimages
+    /// are checked against the given committed boot info.
+    pub fn with_lazy_verification(mut self, boot_info: RawBootInfo) -> Self {
+        self.lazy = Some(LazyVerifier::new(boot_info));
+        self
+    }
+
+    /// Returns the number of preimages that have been verified lazily so far and the number of
+    /// preimages that have not been read, or `None` if lazy verification is not enabled.
+    pub fn lazy_verification_counts(&self) -> Option<(usize, usize)> {
+        self.lazy.as_ref().map(|lazy| {
+            let verified = lazy.verified_count();
+            (verified, self.cache.len() - verified)
+        })
+    }
+
+    /// Reports the counts of [Self::lazy_verification_counts] to the host through the hook at
+    /// [LAZY_VERIFICATION_HOOK_FD], if lazy verification is enabled.
+    #[cfg(target_os = "zkvm")]
+    pub fn report_lazy_verification_counts(&self) {
+        if let Some((verified, unused)) = self.lazy_verification_counts() {
+            let counts = [
+                (verified as u64).to_le_bytes(),
+                (unused as u64).to_le_bytes(),
+            ]
+            .concat();
+            sp1_zkvm::io::write(LAZY_VERIFICATION_HOOK_FD, &counts);
+        }
+    }
+
+    /// Returns the preimage stored under the given key, verifying it first if lazy verification
+    /// is enabled.
+    fn lookup(&self, key: &OracleKey) -> Result<Option<&'static [u8]>> {
+        if let Some(lazy) = &self.lazy {
+            lazy.check(self.cache, key)
+                .map_err(|e| anyhow!("preimage verification failed: {}", e))?;
+        }
+        Ok(self.cache.preimage(key))
+    }
+
+    /// Verifies all data in the oracle against the committed boot info. Once the function has
+    /// been called, all data in the oracle can be trusted for the remainder of execution.
+    pub fn verify(&self, boot_info: &RawBootInfo) -> Result<(), OracleVerifyError> {
+        verify_preimages(self.cache, boot_info)
+    }
+}
+
+impl fmt::Debug for ArchivedInMemoryOracle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedInMemoryOracle")
+            .field("entries", &self.cache.len())
+            .field("lazy", &self.lazy.is_some())
+            .finish()
+    }
+}
+
+impl PreimageStore for ArchivedKvStore {
+    fn preimage(&self, key: &OracleKey) -> Option<&[u8]> {
+        self.get(key).map(ArchivedVec::as_slice)
+    }
+
+    fn preimages(&self) -> Box<dyn Iterator<Item = (&OracleKey, &[u8])> + '_> {
+        Box::new(self.iter().map(|(key, value)| (key, value.as_slice())))
+    }
+}
+
+#[async_trait]
+impl PreimageOracleClient for ArchivedInMemoryOracle {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        let lookup_key = OracleKey::from(key);
+        self.lookup(&lookup_key)?
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Key not found in cache: {}",
+                    hex::encode(lookup_key.as_bytes())
+                )
+            })
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let lookup_key = OracleKey::from(key);
+        let value = self.lookup(&lookup_key)?.ok_or_else(|| {
+            anyhow!(
+                "Key not found in cache (exact): {}",
+                hex::encode(lookup_key.as_bytes())
+            )
+        })?;
+        buf.copy_from_slice(value);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for ArchivedInMemoryOracle {
+    async fn write(&self, _hint: &str) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! Contains the encodings of the serialized key value store passed from the host into the zkVM.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// The largest uncompressed size of an LZ4 encoded key value store. The size is read from stdin,
+/// which the host controls, and is checked before the output buffer is allocated.
+pub const MAX_DECOMPRESSED_SIZE: usize = 1 << 30;
+
+/// How the serialized key value store is encoded in the zkVM's stdin. The host writes the
+/// encoding to stdin ahead of the key value store, so the client knows how to decode it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WitnessEncoding {
+    /// The rkyv bytes of the key value store, as is.
+    #[default]
+    Raw,
+    /// The rkyv bytes of the key value store compressed with LZ4, prefixed with their
+    /// uncompressed size. This makes the stdin much smaller to upload, at the cost of
+    /// decompressing it in the zkVM.
+    Lz4,
+}
+
+impl WitnessEncoding {
+    /// Encodes the rkyv bytes of the key value store.
+    pub fn encode(&self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Self::Raw => bytes,
+            Self::Lz4 => lz4_flex::compress_prepend_size(&bytes),
+        }
+    }
+
+    /// Decodes the key value store bytes read in the zkVM back into its rkyv bytes.
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Self::Raw => Ok(bytes),
+            Self::Lz4 => {
+                println!("cycle-tracker-report-start: oracle-decompress");
+                let decompressed = decompress_lz4(&bytes);
+                println!("cycle-tracker-report-end: oracle-decompress");
+                decompressed
+            }
+        }
+    }
+}
+
+/// Decompresses LZ4 compressed bytes prefixed with their uncompressed size as a little-endian
+/// `u32`, as written by [lz4_flex::compress_prepend_size].
+fn decompress_lz4(bytes: &[u8]) -> Result<Vec<u8>> {
+    let (size, compressed) = bytes
+        .split_first_chunk::<4>()
+        .ok_or_else(|| anyhow!("compressed key value store is missing its size"))?;
+    let size = u32::from_le_bytes(*size) as usize;
+    if size > MAX_DECOMPRESSED_SIZE {
+        return Err(anyhow!(
+            "key value store decompresses to {} bytes, more than the maximum of {}",
+            size,
+            MAX_DECOMPRESSED_SIZE
+        ));
+    }
+
+    let decompressed = lz4_flex::decompress(compressed, size)
+        .map_err(|e| anyhow!("failed to decompress key value store: {}", e))?;
+    if decompressed.len() != size {
+        return Err(anyhow!(
+            "key value store decompressed to {} bytes, not {}",
+            decompressed.len(),
+            size
+        ));
+    }
+    Ok(decompressed)
+}
@@ -0,0 +1,127 @@
+//! Contains the error type returned when verifying the witness data in the oracle.
+
+use alloy_primitives::{hex, FixedBytes};
+use core::fmt;
+use kona_preimage::PreimageKey;
+
+/// An error encountered while verifying the preimages in an [InMemoryOracle].
+///
+/// [InMemoryOracle]: crate::InMemoryOracle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OracleVerifyError {
+    /// The value stored under a keccak256 key does not hash to the key.
+    BadKeccakPreimage(PreimageKey),
+    /// The value stored under a sha256 key does not hash to the key.
+    BadSha256Preimage(PreimageKey),
+    /// A blob element was supplied more than once for the same commitment.
+    BlobOverwrite {
+        /// The commitment of the blob.
+        commitment: FixedBytes<48>,
+        /// The index of the duplicated element.
+        element: usize,
+    },
+    /// A blob element has the wrong length (32 bytes for field elements, 48 for the proof).
+    InvalidBlobElement {
+        /// The commitment of the blob.
+        commitment: FixedBytes<48>,
+        /// The index of the element.
+        element: usize,
+    },
+    /// A blob element was not supplied, so the blob cannot be reconstructed.
+    IncompleteBlob {
+        /// The commitment of the blob.
+        commitment: FixedBytes<48>,
+        /// The index of the first missing element.
+        element: usize,
+    },
+    /// The reconstructed blob does not match its KZG commitment and proof.
+    InvalidBlobProof(FixedBytes<48>),
+    /// The KZG trusted setup or the blob could not be loaded for verification.
+    Kzg(String),
+    /// The keccak256 preimage of a blob key, which holds the commitment and element index, is
+    /// missing or malformed.
+    MissingBlobMetadata(PreimageKey),
+    /// The keccak256 preimage of a precompile key, which holds the precompile address and input,
+    /// is missing or malformed.
+    MissingPrecompileInput(PreimageKey),
+    /// The value stored under a precompile key does not match the result of re-running the
+    /// precompile.
+    BadPrecompileResult(PreimageKey),
+    /// The value stored under a local key does not match the committed boot info.
+    BadLocalPreimage(PreimageKey),
+    /// The local key is not one of the boot info keys.
+    UnknownLocalKey(PreimageKey),
+    /// The oracle does not support verifying preimages of this key's type.
+    UnsupportedKeyType(PreimageKey),
+    /// The key does not have a valid preimage key type.
+    MalformedKey([u8; 32]),
+}
+
+impl fmt::Display for OracleVerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadKeccakPreimage(key) => {
+                write!(f, "bad keccak256 preimage for key {}", encode_key(key))
+            }
+            Self::BadSha256Preimage(key) => {
+                write!(f, "bad sha256 preimage for key {}", encode_key(key))
+            }
+            Self::BlobOverwrite {
+                commitment,
+                element,
+            } => write!(
+                f,
+                "trying to overwrite blob element {} of {}",
+                element, commitment
+            ),
+            Self::InvalidBlobElement {
+                commitment,
+                element,
+            } => write!(
+                f,
+                "blob element {} of {} has an invalid length",
+                element, commitment
+            ),
+            Self::IncompleteBlob {
+                commitment,
+                element,
+            } => write!(
+                f,
+                "blob element {} of {} was not supplied",
+                element, commitment
+            ),
+            Self::InvalidBlobProof(commitment) => {
+                write!(f, "blob kzg proof is invalid for {}", commitment)
+            }
+            Self::Kzg(err) => write!(f, "kzg error: {}", err),
+            Self::MissingBlobMetadata(key) => {
+                write!(f, "missing blob metadata for key {}", encode_key(key))
+            }
+            Self::MissingPrecompileInput(key) => {
+                write!(f, "missing precompile input for key {}", encode_key(key))
+            }
+            Self::BadPrecompileResult(key) => {
+                write!(f, "bad precompile result for key {}", encode_key(key))
+            }
+            Self::BadLocalPreimage(key) => {
+                write!(f, "bad local preimage for key {}", encode_key(key))
+            }
+            Self::UnknownLocalKey(key) => write!(f, "unknown local key {}", encode_key(key)),
+            Self::UnsupportedKeyType(key) => {
+                write!(
+                    f,
+                    "unsupported preimage key type for key {}",
+                    encode_key(key)
+                )
+            }
+            Self::MalformedKey(key) => write!(f, "malformed preimage key {}", hex::encode(key)),
+        }
+    }
+}
+
+impl std::error::Error for OracleVerifyError {}
+
+/// Hex encodes the raw bytes of a [PreimageKey].
+fn encode_key(key: &PreimageKey) -> String {
+    hex::encode(<[u8; 32]>::from(*key))
+}
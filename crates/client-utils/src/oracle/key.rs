@@ -0,0 +1,108 @@
+//! Contains the key type of the oracle's key value store.
+
+use alloy_primitives::hex;
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use rkyv::{Archive, CheckBytes, Deserialize, Serialize};
+
+/// A [PreimageKey] that can be archived with rkyv. The key is stored and archived as its raw 32
+/// bytes, so that lookups in the archived key value store do not need to decode it, but it can
+/// only be created from a [PreimageKey], and its key type is checked when validating an archive.
+#[derive(Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(as = "OracleKey")]
+#[repr(transparent)]
+pub struct OracleKey([u8; 32]);
+
+impl OracleKey {
+    /// Returns the [PreimageKey] this key was created from.
+    pub fn key(&self) -> PreimageKey {
+        PreimageKey::try_from(self.0).expect("oracle keys are created from valid preimage keys")
+    }
+
+    /// Returns the [PreimageKey] this key was created from, or an error if its key type is not
+    /// valid, which is only possible for keys deserialized without validation.
+    pub fn try_key(&self) -> Result<PreimageKey, InvalidOracleKey> {
+        PreimageKey::try_from(self.0).map_err(|_| InvalidOracleKey(self.0))
+    }
+
+    /// Returns the type of the key.
+    pub fn key_type(&self) -> PreimageKeyType {
+        self.key().key_type()
+    }
+
+    /// Returns the raw bytes of the key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<PreimageKey> for OracleKey {
+    fn from(key: PreimageKey) -> Self {
+        Self(key.into())
+    }
+}
+
+impl From<OracleKey> for PreimageKey {
+    fn from(key: OracleKey) -> Self {
+        key.key()
+    }
+}
+
+impl TryFrom<[u8; 32]> for OracleKey {
+    type Error = InvalidOracleKey;
+
+    fn try_from(bytes: [u8; 32]) -> Result<Self, Self::Error> {
+        PreimageKey::try_from(bytes)
+            .map(Self::from)
+            .map_err(|_| InvalidOracleKey(bytes))
+    }
+}
+
+/// Hashes only the raw bytes of the key, without a length prefix, so that the hasher built by
+/// [BytesHasherBuilder] can use them directly.
+///
+/// [BytesHasherBuilder]: crate::BytesHasherBuilder
+impl Hash for OracleKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(&self.0);
+    }
+}
+
+impl fmt::Debug for OracleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OracleKey({})", hex::encode(self.0))
+    }
+}
+
+/// Rejects archived keys that do not have a valid preimage key type.
+impl<C: ?Sized> CheckBytes<C> for OracleKey {
+    type Error = InvalidOracleKey;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        _context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let bytes = *(value as *const [u8; 32]);
+        PreimageKey::try_from(bytes).map_err(|_| InvalidOracleKey(bytes))?;
+        Ok(&*value)
+    }
+}
+
+/// The error returned when raw bytes do not have a valid preimage key type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidOracleKey(pub [u8; 32]);
+
+impl fmt::Display for InvalidOracleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid preimage key type for key {}",
+            hex::encode(self.0)
+        )
+    }
+}
+
+impl std::error::Error for InvalidOracleKey {}
@@ -0,0 +1,134 @@
+//! Contains the state for verifying preimages lazily, the first time they are read from the
+//! oracle, rather than verifying the whole witness up front.
+
+use super::{
+    blob_key, blob_location, keccak_key, load_kzg_settings, verify_blob, verify_hash, verify_local,
+    verify_precompile, Blob, OracleKey, OracleVerifyError, PreimageStore, BLOB_SLOTS,
+};
+use crate::{BytesHasherBuilder, RawBootInfo};
+use alloy_primitives::FixedBytes;
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use std::{collections::HashSet, sync::Mutex};
+
+/// Tracks which preimages have already been verified, so that each preimage is verified at most
+/// once and preimages that are never read are never verified.
+pub(crate) struct LazyVerifier {
+    boot_info: RawBootInfo,
+    verified: Mutex<HashSet<OracleKey, BytesHasherBuilder>>,
+}
+
+impl LazyVerifier {
+    /// Creates a new [LazyVerifier] that checks local preimages against the given boot info.
+    pub(crate) fn new(boot_info: RawBootInfo) -> Self {
+        Self {
+            boot_info,
+            verified: Mutex::new(HashSet::with_hasher(BytesHasherBuilder)),
+        }
+    }
+
+    /// Verifies the preimage stored under the given key, unless it has already been verified.
+    /// Missing keys are skipped, as the oracle will fail to serve them anyway.
+    pub(crate) fn check<S: PreimageStore + ?Sized>(
+        &self,
+        store: &S,
+        key: &OracleKey,
+    ) -> Result<(), OracleVerifyError> {
+        if self.verified.lock().unwrap().contains(key) {
+            return Ok(());
+        }
+        let Some(value) = store.preimage(key) else {
+            return Ok(());
+        };
+
+        println!("cycle-tracker-report-start: oracle-verify-lazy");
+        let result = self.verify(store, key, value);
+        println!("cycle-tracker-report-end: oracle-verify-lazy");
+        result
+    }
+
+    /// Returns the number of preimages that have been verified so far.
+    pub(crate) fn verified_count(&self) -> usize {
+        self.verified.lock().unwrap().len()
+    }
+
+    fn verify<S: PreimageStore + ?Sized>(
+        &self,
+        store: &S,
+        oracle_key: &OracleKey,
+        value: &[u8],
+    ) -> Result<(), OracleVerifyError> {
+        let key = oracle_key
+            .try_key()
+            .map_err(|e| OracleVerifyError::MalformedKey(e.0))?;
+        match key.key_type() {
+            PreimageKeyType::Local => verify_local(key, value, &self.boot_info)?,
+            PreimageKeyType::Keccak256 | PreimageKeyType::Sha256 => verify_hash(key, value)?,
+            // The whole blob is verified on the first access to any of its elements, and all of
+            // its elements are marked as verified.
+            PreimageKeyType::Blob => {
+                let blob_data = self
+                    .keccak_preimage(store, key)?
+                    .ok_or(OracleVerifyError::MissingBlobMetadata(key))?;
+                let (commitment, _) = blob_location(key, blob_data)?;
+                return self.verify_blob(store, commitment);
+            }
+            PreimageKeyType::Precompile => {
+                let hint_data = self
+                    .keccak_preimage(store, key)?
+                    .ok_or(OracleVerifyError::MissingPrecompileInput(key))?;
+                verify_precompile(key, hint_data, value)?;
+            }
+            PreimageKeyType::GlobalGeneric => {
+                return Err(OracleVerifyError::UnsupportedKeyType(key));
+            }
+        }
+
+        self.verified.lock().unwrap().insert(*oracle_key);
+        Ok(())
+    }
+
+    /// Returns the verified keccak256 preimage of a blob or precompile key. Unlike the up front
+    /// verification, the keccak256 preimage is not otherwise guaranteed to have been verified.
+    fn keccak_preimage<'a, S: PreimageStore + ?Sized>(
+        &self,
+        store: &'a S,
+        key: PreimageKey,
+    ) -> Result<Option<&'a [u8]>, OracleVerifyError> {
+        let preimage_key = keccak_key(key);
+        let Some(value) = store.preimage(&preimage_key) else {
+            return Ok(None);
+        };
+        if !self.verified.lock().unwrap().contains(&preimage_key) {
+            self.verify(store, &preimage_key, value)?;
+        }
+        Ok(Some(value))
+    }
+
+    /// Reconstructs the blob with the given commitment from the store and verifies it. The blob
+    /// keys are derived from the commitment, so the keccak256 preimages of the other elements do
+    /// not need to be read.
+    fn verify_blob<S: PreimageStore + ?Sized>(
+        &self,
+        store: &S,
+        commitment: FixedBytes<48>,
+    ) -> Result<(), OracleVerifyError> {
+        let mut blob = Blob::new(commitment);
+        let mut keys = Vec::with_capacity(BLOB_SLOTS);
+        for slot in 0..BLOB_SLOTS {
+            let key = blob_key(&commitment, slot);
+            let value = store
+                .preimage(&key)
+                .ok_or(OracleVerifyError::IncompleteBlob {
+                    commitment,
+                    element: slot,
+                })?;
+            blob.insert(slot, value)?;
+            keys.push(key);
+        }
+
+        verify_blob(&blob, load_kzg_settings()?)?;
+
+        self.verified.lock().unwrap().extend(keys);
+        Ok(())
+    }
+}
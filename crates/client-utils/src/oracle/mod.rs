@@ -2,56 +2,100 @@
 
 use std::collections::HashMap;
 
-use crate::BytesHasherBuilder;
-use alloy_primitives::{hex, keccak256, FixedBytes};
+use crate::{precompiles::precompile_preimage, BytesHasherBuilder, RawBootInfo};
+use alloy_eips::eip4844::{BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB};
+use alloy_primitives::{hex, keccak256, Address, FixedBytes};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use kona_preimage::{HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient};
-use rkyv::{Archive, Deserialize, Infallible, Serialize};
+use kzg_rs::{Blob as KzgBlob, Bytes48, KzgProof, KzgSettings};
 use sha2::{Digest, Sha256};
 
+mod archived;
+pub use archived::{
+    decode_lazy_verification_counts, ArchivedInMemoryOracle, LAZY_VERIFICATION_HOOK_FD,
+};
+
+mod encoding;
+pub use encoding::WitnessEncoding;
+
+mod errors;
+pub use errors::OracleVerifyError;
+
+mod key;
+pub use key::{InvalidOracleKey, OracleKey};
+
+mod lazy;
+use lazy::LazyVerifier;
+
+#[cfg(not(target_os = "zkvm"))]
+mod tracking;
+#[cfg(not(target_os = "zkvm"))]
+pub use tracking::{AccessTrackingOracle, ACCESS_LOG_ENV};
+
+/// The key value store of preimages that is passed from the host into the zkVM.
+pub type KvStore = HashMap<OracleKey, Vec<u8>, BytesHasherBuilder>;
+
+/// Read-only access to a key value store of preimages, so that the same verification can run
+/// against both the deserialized and the archived form of the store.
+pub(crate) trait PreimageStore {
+    /// Returns the preimage stored under the given key, if any.
+    fn preimage(&self, key: &OracleKey) -> Option<&[u8]>;
+
+    /// Returns an iterator over all keys and preimages in the store.
+    fn preimages(&self) -> Box<dyn Iterator<Item = (&OracleKey, &[u8])> + '_>;
+}
+
+impl PreimageStore for KvStore {
+    fn preimage(&self, key: &OracleKey) -> Option<&[u8]> {
+        self.get(key).map(Vec::as_slice)
+    }
+
+    fn preimages(&self) -> Box<dyn Iterator<Item = (&OracleKey, &[u8])> + '_> {
+        Box::new(self.iter().map(|(key, value)| (key, value.as_slice())))
+    }
+}
+
 /// An in-memory HashMap that will serve as the oracle for the zkVM.
 /// Rather than relying on a trusted host for data, the data in this oracle
 /// is verified with the `verify()` function, and then is trusted for
 /// the remainder of execution.
-#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct InMemoryOracle {
-    // TODO: Change this to PreimageKey and everything below.
-    cache: HashMap<[u8; 32], Vec<u8>, BytesHasherBuilder>,
+    cache: KvStore,
 }
 
 impl InMemoryOracle {
-    /// Creates a new [InMemoryOracle] from the raw bytes passed into the zkVM.
-    /// These values are deserialized using rkyv for zero copy deserialization.
-    pub fn from_raw_bytes(input: Vec<u8>) -> Self {
-        let archived = unsafe {
-            rkyv::archived_root::<HashMap<[u8; 32], Vec<u8>, BytesHasherBuilder>>(&input)
-        };
-        let deserialized: HashMap<[u8; 32], Vec<u8>, BytesHasherBuilder> =
-            archived.deserialize(&mut Infallible).unwrap();
+    /// Creates a new [InMemoryOracle] from a key value store loaded on the host. This allows the
+    /// host to run the same verification as the zkVM before proving.
+    pub fn from_kv_store(cache: KvStore) -> Self {
+        Self { cache }
+    }
 
-        Self {
-            cache: deserialized,
-        }
+    /// Consumes the oracle and returns the underlying key value store.
+    pub fn into_kv_store(self) -> KvStore {
+        self.cache
     }
 }
 
 #[async_trait]
 impl PreimageOracleClient for InMemoryOracle {
     async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
-        let lookup_key: [u8; 32] = key.into();
-        self.cache
-            .get(&lookup_key)
-            .cloned()
-            .ok_or_else(|| anyhow!("Key not found in cache: {}", hex::encode(lookup_key)))
+        let lookup_key = OracleKey::from(key);
+        self.cache.get(&lookup_key).cloned().ok_or_else(|| {
+            anyhow!(
+                "Key not found in cache: {}",
+                hex::encode(lookup_key.as_bytes())
+            )
+        })
     }
 
     async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
-        let lookup_key: [u8; 32] = key.into();
+        let lookup_key = OracleKey::from(key);
         let value = self.cache.get(&lookup_key).ok_or_else(|| {
             anyhow!(
                 "Key not found in cache (exact): {}",
-                hex::encode(lookup_key)
+                hex::encode(lookup_key.as_bytes())
             )
         })?;
         buf.copy_from_slice(value.as_slice());
@@ -66,95 +110,270 @@ impl HintWriterClient for InMemoryOracle {
     }
 }
 
+/// The number of slots in a blob: one per field element, plus one for the KZG proof.
+const BLOB_SLOTS: usize = FIELD_ELEMENTS_PER_BLOB as usize + 1;
+
+/// The slot of the KZG proof, which kona stores as the 4096th "element" of the blob.
+const KZG_PROOF_SLOT: usize = FIELD_ELEMENTS_PER_BLOB as usize;
+
 /// A data structure representing a blob. This data is held in memory for future verification.
 /// This is used so that we can aggregate all separate blob elements into a single blob
 /// and verify it once, rather than verifying each of the 4096 elements separately.
-#[derive(Default)]
 struct Blob {
     // TODO: Advantage / disadvantage of using FixedBytes?
     commitment: FixedBytes<48>,
-    data: FixedBytes<4096>,
+    data: FixedBytes<BYTES_PER_BLOB>,
     kzg_proof: FixedBytes<48>,
+    /// Bitmap of the slots (field elements and KZG proof) that have been supplied. Unsupplied
+    /// field elements would otherwise silently be treated as zero.
+    supplied: [u64; BLOB_SLOTS.div_ceil(64)],
+}
+
+impl Blob {
+    fn new(commitment: FixedBytes<48>) -> Self {
+        Self {
+            commitment,
+            data: FixedBytes::ZERO,
+            kzg_proof: FixedBytes::ZERO,
+            supplied: [0; BLOB_SLOTS.div_ceil(64)],
+        }
+    }
+
+    /// Writes the value of the given slot into the blob. Fails if the slot has already been
+    /// supplied or if the value has the wrong length.
+    fn insert(&mut self, slot: usize, value: &[u8]) -> Result<(), OracleVerifyError> {
+        let (word, bit) = (slot / 64, 1u64 << (slot % 64));
+        if self.supplied[word] & bit != 0 {
+            return Err(OracleVerifyError::BlobOverwrite {
+                commitment: self.commitment,
+                element: slot,
+            });
+        }
+
+        let target = if slot == KZG_PROOF_SLOT {
+            self.kzg_proof.as_mut_slice()
+        } else {
+            &mut self.data[slot << 5..(slot + 1) << 5]
+        };
+        if target.len() != value.len() {
+            return Err(OracleVerifyError::InvalidBlobElement {
+                commitment: self.commitment,
+                element: slot,
+            });
+        }
+        target.copy_from_slice(value);
+        self.supplied[word] |= bit;
+
+        Ok(())
+    }
+
+    /// Returns the first slot that has not been supplied, if any.
+    fn first_missing(&self) -> Option<usize> {
+        (0..BLOB_SLOTS).find(|&slot| self.supplied[slot / 64] & (1u64 << (slot % 64)) == 0)
+    }
 }
 
 impl InMemoryOracle {
-    /// Verifies all data in the oracle. Once the function has been called, all data in the
-    /// oracle can be trusted for the remainder of execution.
-    pub fn verify(&self) -> Result<()> {
-        let mut blobs: HashMap<FixedBytes<48>, Blob> = HashMap::new();
-
-        for (key, value) in self.cache.iter() {
-            let key: PreimageKey = <[u8; 32] as TryInto<PreimageKey>>::try_into(*key).unwrap();
-            match key.key_type() {
-                PreimageKeyType::Local => {}
-                PreimageKeyType::Keccak256 => {
-                    let derived_key =
-                        PreimageKey::new(keccak256(value).into(), PreimageKeyType::Keccak256);
-                    assert_eq!(key, derived_key, "zkvm keccak constraint failed!");
-                }
-                PreimageKeyType::GlobalGeneric => {
-                    unimplemented!();
-                }
-                PreimageKeyType::Sha256 => {
-                    let derived_key: [u8; 32] = Sha256::digest(value).into();
-                    let derived_key = PreimageKey::new(derived_key, PreimageKeyType::Sha256);
-                    assert_eq!(key, derived_key, "zkvm sha256 constraint failed!");
-                }
-                // Aggregate blobs and proofs in memory and verify after loop.
-                PreimageKeyType::Blob => {
-                    let blob_data_key: [u8; 32] =
-                        PreimageKey::new(key.into(), PreimageKeyType::Keccak256).into();
-
-                    if let Some(blob_data) = self.cache.get(&blob_data_key) {
-                        let commitment: FixedBytes<48> = blob_data[..48].try_into().unwrap();
-                        let element: [u8; 8] = blob_data[72..].try_into().unwrap();
-                        let element: u64 = u64::from_be_bytes(element);
-
-                        // Blob is stored as one 48 byte element.
-                        if element == 4096 {
-                            blobs
-                                .entry(commitment)
-                                .or_default()
-                                .kzg_proof
-                                .copy_from_slice(value);
-                            continue;
-                        }
-
-                        // Add the 32 bytes of blob data into the correct spot in the blob.
-                        blobs
-                            .entry(commitment)
-                            .or_default()
-                            .data
-                            .get_mut((element as usize) << 5..(element as usize + 1) << 5)
-                            .map(|slice| {
-                                if slice.iter().all(|&byte| byte == 0) {
-                                    slice.copy_from_slice(value);
-                                    Ok(())
-                                } else {
-                                    Err(anyhow!("trying to overwrite existing blob data"))
-                                }
-                            });
-                    } else {
-                        return Err(anyhow!("blob data not found"));
-                    }
-                }
-                PreimageKeyType::Precompile => {
-                    unimplemented!();
-                }
+    /// Verifies all data in the oracle against the committed boot info. Once the function has
+    /// been called, all data in the oracle can be trusted for the remainder of execution.
+    pub fn verify(&self, boot_info: &RawBootInfo) -> Result<(), OracleVerifyError> {
+        verify_preimages(&self.cache, boot_info)
+    }
+}
+
+/// Verifies every preimage in the store against its key. Local preimages are checked against the
+/// committed boot info, keccak256 and sha256 preimages are hashed, blobs are reconstructed and
+/// checked against their KZG commitments, and precompile results are re-computed from their
+/// inputs.
+pub(crate) fn verify_preimages<S: PreimageStore + ?Sized>(
+    store: &S,
+    boot_info: &RawBootInfo,
+) -> Result<(), OracleVerifyError> {
+    let mut blobs: HashMap<FixedBytes<48>, Blob> = HashMap::new();
+
+    for (key, value) in store.preimages() {
+        // The deserialized store is not validated, so its keys may have invalid key types.
+        let key = key
+            .try_key()
+            .map_err(|e| OracleVerifyError::MalformedKey(e.0))?;
+        match key.key_type() {
+            PreimageKeyType::Local => verify_local(key, value, boot_info)?,
+            PreimageKeyType::Keccak256 | PreimageKeyType::Sha256 => verify_hash(key, value)?,
+            // Aggregate blobs and proofs in memory and verify after loop.
+            PreimageKeyType::Blob => {
+                let Some(blob_data) = store.preimage(&keccak_key(key)) else {
+                    return Err(OracleVerifyError::MissingBlobMetadata(key));
+                };
+                let (commitment, element) = blob_location(key, blob_data)?;
+
+                // Add the 32 bytes of blob data (or the 48 byte KZG proof, stored as
+                // element 4096) into the correct spot in the blob.
+                blobs
+                    .entry(commitment)
+                    .or_insert_with(|| Blob::new(commitment))
+                    .insert(element, value)?;
+            }
+            // Re-run the precompile on the committed input and compare the results.
+            PreimageKeyType::Precompile => {
+                let Some(hint_data) = store.preimage(&keccak_key(key)) else {
+                    return Err(OracleVerifyError::MissingPrecompileInput(key));
+                };
+                verify_precompile(key, hint_data, value)?;
+            }
+            PreimageKeyType::GlobalGeneric => {
+                return Err(OracleVerifyError::UnsupportedKeyType(key));
             }
         }
+    }
+
+    // Verify reconstructed blobs against their commitments. The trusted setup is only loaded
+    // if there are blobs to verify, as loading it is expensive inside the zkVM.
+    if !blobs.is_empty() {
+        let kzg_settings = load_kzg_settings()?;
+        for blob in blobs.values() {
+            verify_blob(blob, kzg_settings)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the keccak256 key under which the preimage of a blob or precompile key is stored.
+fn keccak_key(key: PreimageKey) -> OracleKey {
+    PreimageKey::new(key.into(), PreimageKeyType::Keccak256).into()
+}
 
-        // Verify reconstructed blobs.
-        for (commitment, blob) in blobs.iter() {
-            println!("cycle-tracker-start: blob-verification");
-            println!("cycle-tracker-end: blob-verification");
-            // kzg::verify_blob_kzg_proof(&blob.data, commitment, &blob.kzg_proof)
-            // .map_err(|e| format!("blob verification failed for {:?}: {}", commitment, e))?;
+/// Checks that a local preimage matches the corresponding field of the committed boot info, so
+/// that the host cannot serve boot info through the oracle that differs from the public values.
+/// Local keys that are not a boot info field (see [RawBootInfo::local_preimage]) are rejected
+/// with [OracleVerifyError::UnknownLocalKey], rather than skipped, as nothing would check them.
+fn verify_local(
+    key: PreimageKey,
+    value: &[u8],
+    boot_info: &RawBootInfo,
+) -> Result<(), OracleVerifyError> {
+    // Local keys are the index, left padded with zeros.
+    let raw_key: [u8; 32] = key.into();
+    if raw_key[1..24].iter().any(|&b| b != 0) {
+        return Err(OracleVerifyError::UnknownLocalKey(key));
+    }
+    let index = u64::from_be_bytes(raw_key[24..].try_into().unwrap());
+
+    let Some(expected) = boot_info.local_preimage(index) else {
+        return Err(OracleVerifyError::UnknownLocalKey(key));
+    };
+    if expected != value {
+        return Err(OracleVerifyError::BadLocalPreimage(key));
+    }
 
-            // TODO: Would this allow us to leave 000...000 segments in blobs that were not empty and prove that?
-            // May need to track to ensure each blob element has been included.
+    Ok(())
+}
+
+/// Checks that a keccak256 or sha256 preimage hashes to its key.
+fn verify_hash(key: PreimageKey, value: &[u8]) -> Result<(), OracleVerifyError> {
+    match key.key_type() {
+        PreimageKeyType::Keccak256 => {
+            let derived_key = PreimageKey::new(keccak256(value).into(), PreimageKeyType::Keccak256);
+            if key != derived_key {
+                return Err(OracleVerifyError::BadKeccakPreimage(key));
+            }
         }
+        PreimageKeyType::Sha256 => {
+            let derived_key: [u8; 32] = Sha256::digest(value).into();
+            let derived_key = PreimageKey::new(derived_key, PreimageKeyType::Sha256);
+            if key != derived_key {
+                return Err(OracleVerifyError::BadSha256Preimage(key));
+            }
+        }
+        _ => return Err(OracleVerifyError::UnsupportedKeyType(key)),
+    }
 
-        Ok(())
+    Ok(())
+}
+
+/// Decodes the commitment and element index of a blob key from its keccak256 preimage.
+fn blob_location(
+    key: PreimageKey,
+    blob_data: &[u8],
+) -> Result<(FixedBytes<48>, usize), OracleVerifyError> {
+    // The blob key preimage is `abi.encodePacked(commitment, uint256(element))`.
+    if blob_data.len() != 80 || blob_data[48..72].iter().any(|&b| b != 0) {
+        return Err(OracleVerifyError::MissingBlobMetadata(key));
+    }
+    let commitment: FixedBytes<48> = blob_data[..48].try_into().unwrap();
+    let element: [u8; 8] = blob_data[72..].try_into().unwrap();
+    let element: u64 = u64::from_be_bytes(element);
+    if element > FIELD_ELEMENTS_PER_BLOB {
+        return Err(OracleVerifyError::MissingBlobMetadata(key));
+    }
+
+    Ok((commitment, element as usize))
+}
+
+/// Returns the blob key of the given element of the blob with the given commitment.
+fn blob_key(commitment: &FixedBytes<48>, element: usize) -> OracleKey {
+    let mut blob_data = [0u8; 80];
+    blob_data[..48].copy_from_slice(commitment.as_slice());
+    blob_data[72..].copy_from_slice(&(element as u64).to_be_bytes());
+    PreimageKey::new(keccak256(blob_data).into(), PreimageKeyType::Blob).into()
+}
+
+/// Re-runs the precompile on its input and checks that the result matches the preimage.
+fn verify_precompile(
+    key: PreimageKey,
+    hint_data: &[u8],
+    value: &[u8],
+) -> Result<(), OracleVerifyError> {
+    // The precompile key preimage is the address of the precompile followed by its input.
+    if hint_data.len() < 20 {
+        return Err(OracleVerifyError::MissingPrecompileInput(key));
+    }
+    let address = Address::from_slice(&hint_data[..20]);
+
+    println!("cycle-tracker-report-start: precompile-verification");
+    let result = precompile_preimage(address, &hint_data[20..]);
+    println!("cycle-tracker-report-end: precompile-verification");
+
+    if result.as_deref() != Some(value) {
+        return Err(OracleVerifyError::BadPrecompileResult(key));
     }
+
+    Ok(())
+}
+
+/// Returns the KZG trusted setup used to verify blobs.
+fn load_kzg_settings() -> Result<&'static KzgSettings, OracleVerifyError> {
+    crate::kzg::kzg_settings().map_err(|e| OracleVerifyError::Kzg(format!("{:?}", e)))
+}
+
+/// Verifies a reconstructed blob against its commitment and KZG proof.
+fn verify_blob(blob: &Blob, kzg_settings: &KzgSettings) -> Result<(), OracleVerifyError> {
+    // Every field element and the proof must be supplied, so that no part of the blob can be
+    // omitted and treated as zero.
+    if let Some(element) = blob.first_missing() {
+        return Err(OracleVerifyError::IncompleteBlob {
+            commitment: blob.commitment,
+            element,
+        });
+    }
+
+    println!("cycle-tracker-report-start: blob-verification");
+    let valid = KzgProof::verify_blob_kzg_proof(
+        KzgBlob::from_slice(blob.data.as_slice())
+            .map_err(|e| OracleVerifyError::Kzg(format!("{:?}", e)))?,
+        &Bytes48::from_slice(blob.commitment.as_slice())
+            .map_err(|e| OracleVerifyError::Kzg(format!("{:?}", e)))?,
+        &Bytes48::from_slice(blob.kzg_proof.as_slice())
+            .map_err(|e| OracleVerifyError::Kzg(format!("{:?}", e)))?,
+        kzg_settings,
+    );
+    println!("cycle-tracker-report-end: blob-verification");
+
+    // An error is a malformed blob, commitment or proof rather than a proof that does not hold,
+    // so it is reported as such.
+    if !valid.map_err(|e| OracleVerifyError::Kzg(format!("{:?}", e)))? {
+        return Err(OracleVerifyError::InvalidBlobProof(blob.commitment));
+    }
+
+    Ok(())
 }
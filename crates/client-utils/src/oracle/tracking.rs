@@ -0,0 +1,78 @@
+//! Contains an oracle wrapper that records the keys of all preimages read by the native client,
+//! so that the host can prune unread preimages from the witness.
+
+use super::OracleKey;
+use crate::BytesHasherBuilder;
+use alloy_primitives::hex;
+use anyhow::Result;
+use async_trait::async_trait;
+use kona_preimage::{HintWriterClient, PreimageKey, PreimageOracleClient};
+use std::{
+    collections::HashSet,
+    env,
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    sync::Mutex,
+};
+
+/// The environment variable holding the path of the access log. The host sets it only on the
+/// `native-host` process it starts for a run, which passes it on to that run's native client.
+pub const ACCESS_LOG_ENV: &str = "WITNESS_ACCESS_LOG";
+
+/// Wraps an oracle and records the key of every preimage that is read through it.
+#[derive(Debug)]
+pub struct AccessTrackingOracle<O> {
+    inner: O,
+    accessed: Mutex<HashSet<OracleKey, BytesHasherBuilder>>,
+}
+
+impl<O> AccessTrackingOracle<O> {
+    /// Creates a new [AccessTrackingOracle] wrapping the given oracle.
+    pub fn new(inner: O) -> Self {
+        Self {
+            inner,
+            accessed: Mutex::new(HashSet::with_hasher(BytesHasherBuilder)),
+        }
+    }
+
+    /// Appends the hex encoded keys of all preimages read so far to the file at the path in
+    /// [ACCESS_LOG_ENV], one key per line. Does nothing if the variable is not set.
+    pub fn write_access_log(&self) -> Result<()> {
+        let Ok(path) = env::var(ACCESS_LOG_ENV) else {
+            return Ok(());
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        for key in self.accessed.lock().unwrap().iter() {
+            writeln!(writer, "{}", hex::encode(key.as_bytes()))?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn record(&self, key: PreimageKey) {
+        self.accessed.lock().unwrap().insert(key.into());
+    }
+}
+
+#[async_trait]
+impl<O: PreimageOracleClient + Send + Sync> PreimageOracleClient for AccessTrackingOracle<O> {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.record(key);
+        self.inner.get(key).await
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        self.record(key);
+        self.inner.get_exact(key, buf).await
+    }
+}
+
+#[async_trait]
+impl<O: HintWriterClient + Send + Sync> HintWriterClient for AccessTrackingOracle<O> {
+    async fn write(&self, hint: &str) -> Result<()> {
+        self.inner.write(hint).await
+    }
+}
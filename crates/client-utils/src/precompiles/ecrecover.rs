@@ -0,0 +1,60 @@
+//! Contains the `ecrecover` precompile, implemented with `k256` so that the recovery runs on SP1's
+//! secp256k1 syscalls through the patched `ecdsa` crate, regardless of which secp256k1 backend
+//! revm was built with.
+
+use alloy_primitives::{keccak256, B256};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use revm::{
+    precompile::{
+        u64_to_address, Precompile, PrecompileError, PrecompileOutput, PrecompileResult,
+        PrecompileWithAddress,
+    },
+    primitives::Bytes,
+};
+
+/// The gas cost of the `ecrecover` precompile.
+const ECRECOVER_BASE: u64 = 3_000;
+
+/// The `ecrecover` precompile at `0x01`.
+pub(crate) const ECRECOVER: PrecompileWithAddress =
+    PrecompileWithAddress(u64_to_address(1), Precompile::Standard(ec_recover_run));
+
+/// Recovers the address that signed the message, following revm's `ec_recover_run`. An invalid
+/// signature is not an error, and returns an empty output.
+fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if ECRECOVER_BASE > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let mut padded = [0u8; 128];
+    let len = input.len().min(128);
+    padded[..len].copy_from_slice(&input[..len]);
+
+    // `v` must be a 32-byte big-endian integer equal to 27 or 28.
+    if !(padded[32..63].iter().all(|&b| b == 0) && matches!(padded[63], 27 | 28)) {
+        return Ok(PrecompileOutput::new(ECRECOVER_BASE, Bytes::new()));
+    }
+
+    let output = ecrecover(&padded[64..128], padded[63] - 27, &padded[..32])
+        .map(|address| Bytes::copy_from_slice(address.as_slice()))
+        .unwrap_or_default();
+    Ok(PrecompileOutput::new(ECRECOVER_BASE, output))
+}
+
+/// Recovers the public key of the signature over the message hash, and returns its keccak256
+/// hash with the first 12 bytes zeroed, i.e. the address left-padded to 32 bytes.
+fn ecrecover(sig: &[u8], mut recid: u8, msg: &[u8]) -> Option<B256> {
+    let mut sig = Signature::from_slice(sig).ok()?;
+    // A signature with a high `s` is accepted, as in revm, by normalizing it and flipping the
+    // recovery id.
+    if let Some(normalized) = sig.normalize_s() {
+        sig = normalized;
+        recid ^= 1;
+    }
+    let recid = RecoveryId::from_byte(recid)?;
+    let key = VerifyingKey::recover_from_prehash(msg, &sig, recid).ok()?;
+
+    let mut hash = keccak256(&key.to_encoded_point(false).as_bytes()[1..]);
+    hash[..12].fill(0);
+    Some(hash)
+}
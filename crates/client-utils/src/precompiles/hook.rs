@@ -0,0 +1,97 @@
+//! Contains the encoding of the input and output of the precompile hook at
+//! [PRECOMPILE_HOOK_FD](super::PRECOMPILE_HOOK_FD), which runs a precompile on the host.
+
+use revm::{
+    precompile::{PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult},
+    primitives::{Address, Bytes, SpecId},
+};
+
+/// The failure kinds that follow the failure status byte in the output of the hook.
+pub const HOOK_ERROR_PRECOMPILE: u8 = 0;
+pub const HOOK_ERROR_FATAL: u8 = 1;
+pub const HOOK_ERROR_UNKNOWN_PRECOMPILE: u8 = 2;
+pub const HOOK_ERROR_INVALID_INPUT: u8 = 3;
+
+/// The input of the precompile hook. The host runs the precompile with the precompile set of the
+/// spec that was active in the EVM that called it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecompileHookInput {
+    /// The address of the precompile.
+    pub address: Address,
+    /// The spec that was active in the calling EVM.
+    pub spec_id: SpecId,
+    /// The gas limit of the call.
+    pub gas_limit: u64,
+    /// The input of the call.
+    pub input: Bytes,
+}
+
+impl PrecompileHookInput {
+    /// Encodes the input as the address, the spec id byte, the little-endian gas limit and the
+    /// call input.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(29 + self.input.len());
+        buf.extend_from_slice(self.address.as_slice());
+        buf.push(self.spec_id as u8);
+        buf.extend_from_slice(&self.gas_limit.to_le_bytes());
+        buf.extend_from_slice(&self.input);
+        buf
+    }
+
+    /// Decodes an input encoded with [PrecompileHookInput::encode]. Returns `None` if the buffer
+    /// is too short or the spec id is unknown.
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < 29 {
+            return None;
+        }
+        Some(Self {
+            address: Address::from_slice(&buf[..20]),
+            spec_id: SpecId::try_from_u8(buf[20])?,
+            gas_limit: u64::from_le_bytes(buf[21..29].try_into().ok()?),
+            input: Bytes::copy_from_slice(&buf[29..]),
+        })
+    }
+}
+
+/// Encodes the result of a precompile as the output of the hook: `0`, the little-endian gas used
+/// and the precompile output on success, or `1` followed by one of the `HOOK_ERROR_*` kinds on
+/// failure.
+pub fn encode_hook_output(result: &PrecompileResult) -> Vec<u8> {
+    match result {
+        Ok(output) => {
+            let mut buf = Vec::with_capacity(9 + output.bytes.len());
+            buf.push(0);
+            buf.extend_from_slice(&output.gas_used.to_le_bytes());
+            buf.extend_from_slice(&output.bytes);
+            buf
+        }
+        Err(PrecompileErrors::Error(_)) => vec![1, HOOK_ERROR_PRECOMPILE],
+        Err(PrecompileErrors::Fatal { .. }) => vec![1, HOOK_ERROR_FATAL],
+    }
+}
+
+/// Decodes an output encoded with [encode_hook_output]. The host does not send the precompile's
+/// error itself, so failures are returned as generic errors of their kind.
+pub fn decode_hook_output(buf: &[u8]) -> PrecompileResult {
+    match buf {
+        [0, rest @ ..] if rest.len() >= 8 => {
+            let (gas_used, output) = rest.split_at(8);
+            Ok(PrecompileOutput::new(
+                u64::from_le_bytes(gas_used.try_into().unwrap()),
+                Bytes::copy_from_slice(output),
+            ))
+        }
+        [1, HOOK_ERROR_PRECOMPILE] => {
+            Err(PrecompileError::Other("precompile failed on the host".to_string()).into())
+        }
+        [1, HOOK_ERROR_UNKNOWN_PRECOMPILE] => Err(PrecompileErrors::Fatal {
+            msg: "no precompile at the address on the host".to_string(),
+        }),
+        [1, HOOK_ERROR_INVALID_INPUT] => Err(PrecompileErrors::Fatal {
+            msg: "invalid precompile hook input".to_string(),
+        }),
+        _ => Err(PrecompileErrors::Fatal {
+            msg: "invalid precompile hook output".to_string(),
+        }),
+    }
+}
@@ -5,11 +5,24 @@ use kona_executor::PrecompileOverride;
 use kona_mpt::{TrieDB, TrieDBFetcher, TrieDBHinter};
 use revm::{
     handler::register::EvmHandler,
-    precompile::{bn128, Precompile, PrecompileResult, PrecompileSpecId, PrecompileWithAddress},
-    primitives::Bytes,
+    precompile::{
+        bn128, Precompile, PrecompileResult, PrecompileSpecId, PrecompileWithAddress, Precompiles,
+    },
+    primitives::{Address, Bytes, SpecId},
     ContextPrecompiles, State,
 };
 
+#[cfg(feature = "precompile-ecrecover")]
+mod ecrecover;
+mod hook;
+mod p256_verify;
+mod point_evaluation;
+
+pub use hook::{
+    decode_hook_output, encode_hook_output, PrecompileHookInput, HOOK_ERROR_FATAL,
+    HOOK_ERROR_INVALID_INPUT, HOOK_ERROR_PRECOMPILE, HOOK_ERROR_UNKNOWN_PRECOMPILE,
+};
+
 pub const PRECOMPILE_HOOK_FD: u32 = 115;
 
 /// Create an annotated precompile that simply tracks the cycle count of a precompile.
@@ -39,6 +52,126 @@ pub(crate) const ANNOTATED_BN_MUL: PrecompileWithAddress =
     create_annotated_precompile!(bn128::mul::ISTANBUL, "bn-mul");
 pub(crate) const ANNOTATED_BN_PAIR: PrecompileWithAddress =
     create_annotated_precompile!(bn128::pair::ISTANBUL, "bn-pair");
+// Recovers with `k256`, which runs on SP1's secp256k1 syscalls.
+#[cfg(feature = "precompile-ecrecover")]
+pub(crate) const ANNOTATED_ECRECOVER: PrecompileWithAddress =
+    create_annotated_precompile!(ecrecover::ECRECOVER, "ecrecover");
+// Hashes with `sha2`, which is patched to run on SP1's SHA-256 syscalls.
+#[cfg(feature = "precompile-sha256")]
+pub(crate) const ANNOTATED_SHA256: PrecompileWithAddress =
+    create_annotated_precompile!(revm::precompile::hash::SHA256, "sha256");
+// SP1 has no syscall for RIPEMD-160 or modular exponentiation, so these only track the cycle count.
+#[cfg(feature = "precompile-ripemd160")]
+pub(crate) const ANNOTATED_RIPEMD160: PrecompileWithAddress =
+    create_annotated_precompile!(revm::precompile::hash::RIPEMD160, "ripemd160");
+// OP Stack chains start at Bedrock, which is based on London, so the Berlin pricing always applies.
+#[cfg(feature = "precompile-modexp")]
+pub(crate) const ANNOTATED_MODEXP: PrecompileWithAddress =
+    create_annotated_precompile!(revm::precompile::modexp::BERLIN, "modexp");
+
+/// The ZKVM-accelerated precompiles and annotated precompiles that track the cycle count. Each
+/// precompile other than the BN254 ones can be turned off with its `precompile-*` feature, in
+/// which case revm's implementation is used without tracking.
+pub(crate) const ZKVM_PRECOMPILES: &[PrecompileWithAddress] = &[
+    ANNOTATED_BN_ADD,
+    ANNOTATED_BN_MUL,
+    ANNOTATED_BN_PAIR,
+    #[cfg(feature = "precompile-ecrecover")]
+    ANNOTATED_ECRECOVER,
+    #[cfg(feature = "precompile-sha256")]
+    ANNOTATED_SHA256,
+    #[cfg(feature = "precompile-ripemd160")]
+    ANNOTATED_RIPEMD160,
+    #[cfg(feature = "precompile-modexp")]
+    ANNOTATED_MODEXP,
+];
+
+pub(crate) const ANNOTATED_KZG_POINT_EVALUATION: PrecompileWithAddress =
+    create_annotated_precompile!(point_evaluation::POINT_EVALUATION, "kzg-point-evaluation");
+
+/// The ZKVM precompiles that are only active from Cancun (Ecotone on OP Stack chains).
+pub(crate) const CANCUN_ZKVM_PRECOMPILES: &[PrecompileWithAddress] =
+    &[ANNOTATED_KZG_POINT_EVALUATION];
+
+// SP1 has no syscall for secp256r1, so this only tracks the cycle count.
+#[cfg(feature = "precompile-p256verify")]
+pub(crate) const ANNOTATED_P256VERIFY: PrecompileWithAddress =
+    create_annotated_precompile!(p256_verify::P256VERIFY, "p256-verify");
+
+/// The ZKVM precompiles that are only active from Fjord. revm's precompile sets do not include
+/// `P256VERIFY`, so it is always added, and its feature only turns off the tracking.
+pub(crate) const FJORD_ZKVM_PRECOMPILES: &[PrecompileWithAddress] = &[
+    #[cfg(feature = "precompile-p256verify")]
+    ANNOTATED_P256VERIFY,
+    #[cfg(not(feature = "precompile-p256verify"))]
+    p256_verify::P256VERIFY,
+];
+
+/// Returns the ZKVM precompiles that are active in the given spec, which replace revm's
+/// precompiles at the same addresses.
+fn zkvm_precompiles(spec_id: SpecId) -> impl Iterator<Item = &'static PrecompileWithAddress> {
+    let cancun: &'static [PrecompileWithAddress] = if SpecId::enabled(spec_id, SpecId::CANCUN) {
+        CANCUN_ZKVM_PRECOMPILES
+    } else {
+        &[]
+    };
+    // The executor derives the spec id from the rollup config, so this follows
+    // `RollupConfig::is_fjord_active`.
+    let fjord: &'static [PrecompileWithAddress] = if SpecId::enabled(spec_id, SpecId::FJORD) {
+        FJORD_ZKVM_PRECOMPILES
+    } else {
+        &[]
+    };
+    ZKVM_PRECOMPILES.iter().chain(cancun).chain(fjord)
+}
+
+/// Returns the precompile at the given address in the precompile set of [ZKVMPrecompileOverride]
+/// for the given spec, so that code outside the zkVM, such as the host's precompile hook, runs
+/// the same precompiles with the same gas costs as the guest.
+pub fn zkvm_precompile(spec_id: SpecId, address: &Address) -> Option<Precompile> {
+    zkvm_precompiles(spec_id)
+        .find(|precompile| precompile.0 == *address)
+        .map(|precompile| precompile.1.clone())
+        .or_else(|| {
+            Precompiles::new(PrecompileSpecId::from_spec_id(spec_id))
+                .get(address)
+                .cloned()
+        })
+}
+
+/// Computes the value of a precompile preimage ([PreimageKeyType::Precompile]) for the given
+/// precompile address and input, in the format written by kona's host: a status byte (`0x01` on
+/// success, `0x00` on failure) followed by the output. The precompile is run with the same
+/// implementations as [ZKVMPrecompileOverride], so results provided by the host can be verified
+/// in the zkVM.
+///
+/// Returns `None` if there is no standard precompile at the given address.
+///
+/// [PreimageKeyType::Precompile]: kona_preimage::PreimageKeyType::Precompile
+pub fn precompile_preimage(address: Address, input: &[u8]) -> Option<Vec<u8>> {
+    // Note: kona's host executes precompiles with the latest precompile set.
+    let precompile = ZKVM_PRECOMPILES
+        .iter()
+        .chain(CANCUN_ZKVM_PRECOMPILES)
+        .chain(FJORD_ZKVM_PRECOMPILES)
+        .find(|precompile| precompile.0 == address)
+        .map(|precompile| precompile.1.clone())
+        .or_else(|| Precompiles::latest().get(&address).cloned())?;
+
+    let Precompile::Standard(precompile) = precompile else {
+        return None;
+    };
+
+    match precompile(&Bytes::copy_from_slice(input), u64::MAX) {
+        Ok(output) => {
+            let mut result = Vec::with_capacity(1 + output.bytes.len());
+            result.push(0x01);
+            result.extend_from_slice(&output.bytes);
+            Some(result)
+        }
+        Err(_) => Some(vec![0x00]),
+    }
+}
 
 /// The [PrecompileOverride] implementation for the FPVM-accelerated precompiles.
 #[derive(Debug)]
@@ -75,12 +208,7 @@ where
                 ContextPrecompiles::new(PrecompileSpecId::from_spec_id(spec_id)).clone();
 
             // Extend with ZKVM-accelerated precompiles and annotated precompiles that track the cycle count.
-            let override_precompiles = [
-                ANNOTATED_BN_ADD,
-                ANNOTATED_BN_MUL,
-                ANNOTATED_BN_PAIR,
-            ];
-            ctx_precompiles.extend(override_precompiles);
+            ctx_precompiles.extend(zkvm_precompiles(spec_id).cloned());
 
             ctx_precompiles
         });
@@ -0,0 +1,48 @@
+//! Contains the RIP-7212 `P256VERIFY` precompile, implemented with `p256` so that it is available
+//! regardless of the features revm was built with.
+
+use alloy_primitives::B256;
+use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use revm::{
+    precompile::{
+        u64_to_address, Precompile, PrecompileError, PrecompileOutput, PrecompileResult,
+        PrecompileWithAddress,
+    },
+    primitives::Bytes,
+};
+
+/// The gas cost of the `P256VERIFY` precompile.
+const P256VERIFY_BASE: u64 = 3_450;
+
+/// The `P256VERIFY` precompile at `0x100`, which is active from Fjord.
+pub(crate) const P256VERIFY: PrecompileWithAddress =
+    PrecompileWithAddress(u64_to_address(0x100), Precompile::Standard(p256_verify));
+
+/// Verifies a secp256r1 signature over a message hash, following revm's `p256_verify`. Returns
+/// `1` as a 32-byte word if the signature is valid, and an empty output otherwise.
+fn p256_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if P256VERIFY_BASE > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+    let output = if verify(input).is_some() {
+        B256::with_last_byte(1).into()
+    } else {
+        Bytes::new()
+    };
+    Ok(PrecompileOutput::new(P256VERIFY_BASE, output))
+}
+
+/// Verifies the signature in the input, which is the message hash, the `r` and `s` values of the
+/// signature and the `x` and `y` coordinates of the public key.
+fn verify(input: &[u8]) -> Option<()> {
+    if input.len() != 160 {
+        return None;
+    }
+    let mut public_key = [0u8; 65];
+    public_key[0] = 0x04;
+    public_key[1..].copy_from_slice(&input[96..160]);
+
+    let signature = Signature::from_slice(&input[32..96]).ok()?;
+    let public_key = VerifyingKey::from_sec1_bytes(&public_key).ok()?;
+    public_key.verify_prehash(&input[..32], &signature).ok()
+}
@@ -0,0 +1,75 @@
+//! Contains the KZG point evaluation precompile, implemented with `kzg-rs`, as revm's
+//! implementation depends on `c-kzg`, which does not build for the zkVM.
+
+use alloy_primitives::hex;
+use kzg_rs::{Bytes32, Bytes48, KzgProof};
+use revm::{
+    precompile::{
+        u64_to_address, Precompile, PrecompileError, PrecompileErrors, PrecompileOutput,
+        PrecompileResult, PrecompileWithAddress,
+    },
+    primitives::Bytes,
+};
+use sha2::{Digest, Sha256};
+
+use crate::kzg::kzg_settings;
+
+/// The gas cost of the point evaluation precompile.
+const GAS_COST: u64 = 50_000;
+
+/// The version byte of versioned hashes of KZG commitments.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The output of a successful point evaluation: the number of field elements per blob and the
+/// modulus of the BLS12-381 scalar field.
+const RETURN_VALUE: [u8; 64] = hex!(
+    "0000000000000000000000000000000000000000000000000000000000001000"
+    "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+);
+
+/// The KZG point evaluation precompile at `0x0a`, which is active from Cancun.
+pub(crate) const POINT_EVALUATION: PrecompileWithAddress =
+    PrecompileWithAddress(u64_to_address(0x0A), Precompile::Standard(run));
+
+/// Verifies that the polynomial committed to by the commitment evaluates to `y` at `z`,
+/// following revm's `kzg_point_evaluation::run`. The input is the versioned hash of the
+/// commitment, `z`, `y`, the commitment and the proof.
+fn run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if gas_limit < GAS_COST {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+    if input.len() != 192 {
+        return Err(PrecompileError::BlobInvalidInputLength.into());
+    }
+
+    let versioned_hash = &input[..32];
+    let commitment = &input[96..144];
+    let mut expected_hash: [u8; 32] = Sha256::digest(commitment).into();
+    expected_hash[0] = VERSIONED_HASH_VERSION_KZG;
+    if versioned_hash != expected_hash {
+        return Err(PrecompileError::BlobMismatchedVersion.into());
+    }
+
+    let kzg_settings = kzg_settings().map_err(|e| PrecompileErrors::Fatal {
+        msg: format!("failed to load the KZG trusted setup: {:?}", e),
+    })?;
+
+    // The slices have the exact lengths, so any error is an invalid commitment, point or proof.
+    let valid = (|| {
+        KzgProof::verify_kzg_proof(
+            &Bytes48::from_slice(commitment)?,
+            &Bytes32::from_slice(&input[32..64])?,
+            &Bytes32::from_slice(&input[64..96])?,
+            &Bytes48::from_slice(&input[144..192])?,
+            kzg_settings,
+        )
+    })();
+    if !matches!(valid, Ok(true)) {
+        return Err(PrecompileError::BlobVerifyKzgProofFailed.into());
+    }
+
+    Ok(PrecompileOutput::new(
+        GAS_COST,
+        Bytes::copy_from_slice(&RETURN_VALUE),
+    ))
+}
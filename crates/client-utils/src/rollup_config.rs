@@ -0,0 +1,140 @@
+//! The canonical encoding of the rollup config that the boot info commits to the hash of.
+//!
+//! The encoding is explicit and field by field rather than derived from a serde format, so that
+//! the committed hash only changes when the config does, and not when the field names, the order
+//! of JSON keys or the serde attributes of the config types change upstream. Bump
+//! [ROLLUP_CONFIG_HASH_VERSION] whenever the encoding changes, including when a field is added.
+
+use alloc::vec::Vec;
+use alloy_eips::eip1559::BaseFeeParams;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use kona_primitives::{BlockID, ChainGenesis, RollupConfig, SystemConfig};
+
+/// The version of the rollup config encoding, which is its first byte.
+pub const ROLLUP_CONFIG_HASH_VERSION: u8 = 1;
+
+/// Returns the keccak256 hash of the canonical encoding of the rollup config, which is what the
+/// boot info commits to.
+///
+/// The encoding is [ROLLUP_CONFIG_HASH_VERSION] followed by the fields of the config in a
+/// fixed order. Integers are big-endian and fixed width, addresses are their 20 bytes and
+/// optional values are a `0` byte if unset, or a `1` byte followed by the value.
+pub fn hash_rollup_config(rollup_config: &RollupConfig) -> B256 {
+    let mut out = Vec::with_capacity(512);
+    ROLLUP_CONFIG_HASH_VERSION.encode(&mut out);
+    rollup_config.encode(&mut out);
+    keccak256(out)
+}
+
+/// A value with a canonical byte encoding.
+trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+impl Encode for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Encode for u128 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Encode for U256 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes::<32>());
+    }
+}
+
+impl Encode for B256 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_slice());
+    }
+}
+
+impl Encode for Address {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_slice());
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => 0u8.encode(out),
+            Some(value) => {
+                1u8.encode(out);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl Encode for BlockID {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.hash.encode(out);
+        self.number.encode(out);
+    }
+}
+
+impl Encode for SystemConfig {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.batcher_address.encode(out);
+        self.overhead.encode(out);
+        self.scalar.encode(out);
+        self.gas_limit.encode(out);
+        self.base_fee_scalar.encode(out);
+        self.blob_base_fee_scalar.encode(out);
+    }
+}
+
+impl Encode for ChainGenesis {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.l1.encode(out);
+        self.l2.encode(out);
+        self.l2_time.encode(out);
+        self.system_config.encode(out);
+    }
+}
+
+impl Encode for BaseFeeParams {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.max_change_denominator.encode(out);
+        self.elasticity_multiplier.encode(out);
+    }
+}
+
+impl Encode for RollupConfig {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.genesis.encode(out);
+        self.block_time.encode(out);
+        self.max_sequencer_drift.encode(out);
+        self.seq_window_size.encode(out);
+        self.channel_timeout.encode(out);
+        self.l1_chain_id.encode(out);
+        self.l2_chain_id.encode(out);
+        self.base_fee_params.encode(out);
+        self.canyon_base_fee_params.encode(out);
+        self.regolith_time.encode(out);
+        self.canyon_time.encode(out);
+        self.delta_time.encode(out);
+        self.ecotone_time.encode(out);
+        self.fjord_time.encode(out);
+        self.batch_inbox_address.encode(out);
+        self.deposit_contract_address.encode(out);
+        self.l1_system_config_address.encode(out);
+        self.protocol_versions_address.encode(out);
+        self.superchain_config_address.encode(out);
+        self.blobs_enabled_l1_timestamp.encode(out);
+        self.da_challenge_address.encode(out);
+    }
+}
@@ -0,0 +1,128 @@
+//! Contains the derivation trace that the native client can write, describing how each L2 block
+//! in the range was derived, so failed proofs can be compared against op-node.
+
+use crate::driver::{MultiBlockDerivationDriver, DEPOSIT_TX_TYPE};
+use alloy_primitives::B256;
+use anyhow::Result;
+use core::fmt::Debug;
+use kona_derive::traits::OriginProvider;
+use kona_preimage::CommsClient;
+use kona_primitives::{L2BlockInfo, L2PayloadAttributes};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+/// The environment variable holding the path of the derivation trace. The host sets it only on
+/// the `native-host` process it starts for a run, which passes it on to that run's native client.
+pub const DERIVATION_TRACE_ENV: &str = "DERIVATION_TRACE";
+
+/// How a single L2 block was derived.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTrace {
+    /// The number of the L2 block.
+    pub number: u64,
+    /// The hash of the L2 block.
+    pub hash: B256,
+    /// The number of the L1 origin of the L2 block.
+    pub l1_origin_number: u64,
+    /// The hash of the L1 origin of the L2 block.
+    pub l1_origin_hash: B256,
+    /// The number of the L1 block the pipeline was reading when the block was derived, if it had
+    /// an origin. The batch of the block was included in this block or an earlier one.
+    pub pipeline_origin: Option<u64>,
+    /// The number of transactions in the block, including deposits.
+    pub transactions: usize,
+    /// The number of deposit transactions in the block.
+    pub deposits: usize,
+    /// Whether the derived payload failed to execute and was replaced with a deposit-only block.
+    pub deposit_only: bool,
+    /// The number of pipeline steps taken since the previous block was derived.
+    pub steps: u64,
+    /// The number of L1 origin advances since the previous block was derived.
+    pub origin_advances: u64,
+}
+
+/// The [BlockTrace] of every L2 block derived by the native client, in order.
+///
+/// The trace is written as it is recorded, as JSON with one block per line, because the native
+/// client aborts on panic without unwinding, and the blocks derived before a failure are the
+/// ones needed to debug it.
+#[derive(Debug, Default)]
+pub struct DerivationTrace {
+    /// The traces of the derived blocks.
+    pub blocks: Vec<BlockTrace>,
+    file: Option<File>,
+    last_steps: u64,
+    last_origin_advances: u64,
+}
+
+impl DerivationTrace {
+    /// Creates an empty trace that is written to the file at the path in [DERIVATION_TRACE_ENV],
+    /// or not written at all if the variable is not set.
+    pub fn from_env() -> Result<Self> {
+        let file = match env::var(DERIVATION_TRACE_ENV) {
+            Ok(path) => Some(File::create(path)?),
+            Err(_) => None,
+        };
+        Ok(Self {
+            file,
+            ..Default::default()
+        })
+    }
+
+    /// Records a block that was derived by the driver and executed with the given attributes,
+    /// and appends it to the trace file.
+    pub fn record_block<O: CommsClient + Send + Sync + Debug>(
+        &mut self,
+        driver: &MultiBlockDerivationDriver<O>,
+        l2_block_info: &L2BlockInfo,
+        attributes: &L2PayloadAttributes,
+        deposit_only: bool,
+    ) -> Result<()> {
+        let (steps, origin_advances) = (driver.steps(), driver.origin_advances());
+        let block = BlockTrace {
+            number: l2_block_info.block_info.number,
+            hash: l2_block_info.block_info.hash,
+            l1_origin_number: l2_block_info.l1_origin.number,
+            l1_origin_hash: l2_block_info.l1_origin.hash,
+            pipeline_origin: driver.pipeline.origin().map(|origin| origin.number),
+            transactions: attributes.transactions.len(),
+            deposits: attributes
+                .transactions
+                .iter()
+                .filter(|raw_tx| raw_tx.first() == Some(&DEPOSIT_TX_TYPE))
+                .count(),
+            deposit_only,
+            steps: steps - self.last_steps,
+            origin_advances: origin_advances - self.last_origin_advances,
+        };
+        self.last_steps = steps;
+        self.last_origin_advances = origin_advances;
+
+        // Each block is written with a single write, so a trace cut short by an abort ends with
+        // the last complete block.
+        if let Some(file) = &mut self.file {
+            let mut line = serde_json::to_vec(&block)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+        }
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// Reads the blocks of a trace written by the native client.
+    pub fn read(path: &Path) -> Result<Self> {
+        let blocks = fs::read_to_string(path)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            blocks,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,46 @@
+//! Tests of decoding the data of batcher transactions on chains that use alt-DA.
+
+use alloy_primitives::{Bytes, B256};
+use client_utils::altda::AltDAData;
+
+#[test]
+fn frames_are_passed_through() {
+    // Frames start with the derivation version, which is 0x00.
+    let data = Bytes::from_static(&[0x00, 0xAA, 0xBB]);
+    assert_eq!(AltDAData::decode(data.clone()), AltDAData::Frames(data));
+    assert_eq!(
+        AltDAData::decode(Bytes::new()),
+        AltDAData::Frames(Bytes::new())
+    );
+}
+
+#[test]
+fn keccak_commitments_are_resolved() {
+    let commitment = B256::repeat_byte(0x42);
+    let data = Bytes::from([[0x01, 0x00].as_slice(), commitment.as_slice()].concat());
+    assert_eq!(AltDAData::decode(data), AltDAData::Keccak(commitment));
+}
+
+#[test]
+fn unsupported_commitments_are_skipped() {
+    let commitment = B256::repeat_byte(0x42);
+
+    // A generic commitment, which is only served by a DA server in generic mode.
+    let generic = Bytes::from([[0x01, 0x01].as_slice(), commitment.as_slice()].concat());
+    assert_eq!(
+        AltDAData::decode(generic.clone()),
+        AltDAData::Unsupported(generic)
+    );
+
+    // Truncated and overlong keccak256 commitments.
+    for data in [
+        Bytes::from_static(&[0x01]),
+        Bytes::from([[0x01, 0x00].as_slice(), &commitment[..31]].concat()),
+        Bytes::from([[0x01, 0x00].as_slice(), commitment.as_slice(), &[0x00]].concat()),
+    ] {
+        assert_eq!(
+            AltDAData::decode(data.clone()),
+            AltDAData::Unsupported(data)
+        );
+    }
+}
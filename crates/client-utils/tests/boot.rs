@@ -0,0 +1,60 @@
+//! Tests of the boot info that the host writes to the stdin of the programs.
+
+use alloy_primitives::B256;
+use client_utils::{hash_rollup_config, RawBootInfo};
+use kona_client::BootInfo;
+use kona_primitives::RollupConfig;
+
+fn raw_boot_info(rollup_config: Option<RollupConfig>) -> RawBootInfo {
+    let rollup_config_hash =
+        hash_rollup_config(&RollupConfig::from_l2_chain_id(10).expect("OP Mainnet is registered"));
+    RawBootInfo {
+        l1_head: B256::repeat_byte(0x01),
+        l2_output_root: B256::repeat_byte(0x02),
+        l2_output_root_block: 0,
+        l2_claim: B256::repeat_byte(0x03),
+        l2_claim_block: 100,
+        chain_id: 10,
+        rollup_config_hash,
+        rollup_config,
+    }
+}
+
+#[test]
+fn supplied_rollup_config_survives_stdin_encoding() {
+    let rollup_config = RollupConfig::from_l2_chain_id(10).unwrap();
+    let raw_boot_info = raw_boot_info(Some(rollup_config.clone()));
+
+    // The stdin of the programs is bincode encoded.
+    let encoded = bincode::serialize(&raw_boot_info).unwrap();
+    let decoded: RawBootInfo = bincode::deserialize(&encoded).unwrap();
+    assert_eq!(
+        decoded.rollup_config.as_ref().map(hash_rollup_config),
+        Some(hash_rollup_config(&rollup_config))
+    );
+
+    let boot_info: BootInfo = decoded.into();
+    assert_eq!(
+        hash_rollup_config(&boot_info.rollup_config),
+        raw_boot_info.rollup_config_hash
+    );
+}
+
+#[test]
+fn registry_rollup_config_is_used_when_none_is_supplied() {
+    let raw_boot_info = raw_boot_info(None);
+    let encoded = bincode::serialize(&raw_boot_info).unwrap();
+    let decoded: RawBootInfo = bincode::deserialize(&encoded).unwrap();
+    assert!(decoded.rollup_config.is_none());
+
+    let boot_info: BootInfo = decoded.into();
+    assert_eq!(boot_info.rollup_config.l2_chain_id, 10);
+}
+
+#[test]
+#[should_panic(expected = "rollup config does not match the committed hash")]
+fn rollup_config_must_match_the_committed_hash() {
+    let mut rollup_config = RollupConfig::from_l2_chain_id(10).unwrap();
+    rollup_config.block_time += 1;
+    let _: BootInfo = raw_boot_info(Some(rollup_config)).into();
+}
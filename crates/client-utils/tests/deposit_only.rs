@@ -0,0 +1,92 @@
+//! Tests of when a payload that failed to execute is replaced with a deposit-only block.
+
+use std::sync::Arc;
+
+use alloy_primitives::{Bytes, B256};
+use anyhow::anyhow;
+use client_utils::{
+    driver::deposit_only_fallback, l2_chain_provider::MultiblockOracleL2ChainProvider,
+    InMemoryOracle, KvStore,
+};
+use kona_client::BootInfo;
+use kona_mpt::TrieDBFetcher;
+use kona_primitives::{L2PayloadAttributes, RollupConfig};
+
+/// Returns a provider backed by an oracle without any preimages, i.e. a host that withheld the
+/// whole witness.
+fn provider() -> MultiblockOracleL2ChainProvider<InMemoryOracle> {
+    let boot_info = BootInfo {
+        l1_head: B256::ZERO,
+        l2_output_root: B256::ZERO,
+        l2_claim: B256::ZERO,
+        l2_claim_block: 0,
+        chain_id: 10,
+        rollup_config: RollupConfig::default(),
+    };
+    MultiblockOracleL2ChainProvider::new(
+        Arc::new(boot_info),
+        Arc::new(InMemoryOracle::from_kv_store(KvStore::default())),
+    )
+}
+
+/// Returns attributes with a deposit and a user transaction.
+fn attributes() -> L2PayloadAttributes {
+    L2PayloadAttributes {
+        transactions: vec![
+            Bytes::from_static(&[0x7E, 0x01]),
+            Bytes::from_static(&[0x02, 0x01]),
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn withheld_preimage_aborts_instead_of_deposit_only() {
+    let provider = provider();
+    let failed_fetches = provider.failed_fetches();
+
+    // The executor fails because a trie node it needs is not in the witness.
+    let error = provider
+        .trie_node_preimage(B256::repeat_byte(0x11))
+        .expect_err("preimage should be missing");
+    assert_eq!(provider.failed_fetches(), failed_fetches + 1);
+
+    let result = deposit_only_fallback(&provider, failed_fetches, &attributes(), error);
+    assert!(
+        result.is_err(),
+        "missing witness data produced a deposit-only block"
+    );
+}
+
+#[test]
+fn invalid_payload_falls_back_to_deposit_only() {
+    let provider = provider();
+    let failed_fetches = provider.failed_fetches();
+
+    let deposit_only = deposit_only_fallback(
+        &provider,
+        failed_fetches,
+        &attributes(),
+        anyhow!("Transaction gas limit exceeds block gas limit"),
+    )
+    .expect("invalid payload should fall back");
+    assert_eq!(
+        deposit_only.transactions,
+        vec![Bytes::from_static(&[0x7E, 0x01])]
+    );
+}
+
+#[test]
+fn failing_deposit_only_payload_aborts() {
+    let provider = provider();
+    let mut attributes = attributes();
+    attributes.transactions.truncate(1);
+
+    let result = deposit_only_fallback(
+        &provider,
+        provider.failed_fetches(),
+        &attributes,
+        anyhow!("invalid deposit"),
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,29 @@
+//! Tests of the encodings of the key value store passed into the zkVM.
+
+use client_utils::WitnessEncoding;
+
+#[test]
+fn lz4_round_trips() {
+    let bytes: Vec<u8> = (0..10_000u32).flat_map(|i| (i % 7).to_le_bytes()).collect();
+    for encoding in [WitnessEncoding::Raw, WitnessEncoding::Lz4] {
+        let encoded = encoding.encode(bytes.clone());
+        assert_eq!(encoding.decode(encoded).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn lz4_rejects_oversized_prefix() {
+    // The host claims the store decompresses to 4 GiB, which must fail before allocating.
+    let mut encoded = WitnessEncoding::Lz4.encode(vec![0; 64]);
+    encoded[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(WitnessEncoding::Lz4.decode(encoded).is_err());
+}
+
+#[test]
+fn lz4_rejects_wrong_prefix() {
+    let mut encoded = WitnessEncoding::Lz4.encode(vec![0; 64]);
+    encoded[..4].copy_from_slice(&128u32.to_le_bytes());
+    assert!(WitnessEncoding::Lz4.decode(encoded).is_err());
+
+    assert!(WitnessEncoding::Lz4.decode(vec![0; 3]).is_err());
+}
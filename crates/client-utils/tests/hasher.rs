@@ -0,0 +1,30 @@
+//! Tests of the hasher of the oracle's key value store.
+
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use alloy_primitives::B256;
+use client_utils::{BytesHasherBuilder, OracleKey};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+#[test]
+fn oracle_keys_hash_to_their_first_8_bytes() {
+    let key: OracleKey =
+        PreimageKey::new(*B256::repeat_byte(0x11), PreimageKeyType::Keccak256).into();
+    let expected = u64::from_be_bytes(key.as_bytes()[..8].try_into().unwrap());
+    assert_eq!(BytesHasherBuilder.hash_one(key), expected);
+}
+
+#[test]
+fn short_writes_do_not_panic() {
+    // A slice is hashed with a length prefix, which is 4 bytes on the 32-bit zkVM.
+    let mut hasher = BytesHasherBuilder.build_hasher();
+    hasher.write_u32(32);
+    hasher.write(&[0x01; 3]);
+    assert_eq!(hasher.finish(), 0);
+
+    // The prefix is skipped or overwritten by the bytes that follow it.
+    let bytes = [0x22u8; 32];
+    let mut hasher = BytesHasherBuilder.build_hasher();
+    bytes.as_slice().hash(&mut hasher);
+    assert_eq!(hasher.finish(), u64::from_be_bytes([0x22; 8]));
+}
@@ -0,0 +1,67 @@
+//! Tests of the verification of local preimages against the committed boot info.
+
+use alloy_primitives::B256;
+use client_utils::{InMemoryOracle, KvStore, OracleVerifyError, RawBootInfo};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+fn boot_info() -> RawBootInfo {
+    RawBootInfo {
+        l1_head: B256::repeat_byte(0x01),
+        l2_output_root: B256::repeat_byte(0x02),
+        l2_output_root_block: 0,
+        l2_claim: B256::repeat_byte(0x03),
+        l2_claim_block: 100,
+        chain_id: 10,
+        rollup_config_hash: B256::ZERO,
+        rollup_config: None,
+    }
+}
+
+/// Verifies a witness that only holds the given local preimage.
+fn verify(key: PreimageKey, value: Vec<u8>) -> Result<(), OracleVerifyError> {
+    let mut kv_store = KvStore::default();
+    kv_store.insert(key.into(), value);
+    InMemoryOracle::from_kv_store(kv_store).verify(&boot_info())
+}
+
+#[test]
+fn boot_info_keys_are_checked() {
+    let boot_info = boot_info();
+    for index in 1..=5 {
+        let expected = boot_info.local_preimage(index).unwrap();
+        assert_eq!(
+            verify(PreimageKey::new_local(index), expected.clone()),
+            Ok(())
+        );
+
+        let mut wrong = expected;
+        wrong[0] ^= 1;
+        let key = PreimageKey::new_local(index);
+        assert_eq!(
+            verify(key, wrong),
+            Err(OracleVerifyError::BadLocalPreimage(key))
+        );
+    }
+}
+
+#[test]
+fn unknown_local_keys_are_rejected() {
+    // Index 0 and indices past the boot info fields have no committed value.
+    for index in [0, 6, u64::MAX] {
+        let key = PreimageKey::new_local(index);
+        assert_eq!(
+            verify(key, vec![0x01]),
+            Err(OracleVerifyError::UnknownLocalKey(key))
+        );
+    }
+
+    // So do keys that are not a left padded index.
+    let mut raw_key = [0u8; 32];
+    raw_key[1] = 0x01;
+    raw_key[31] = 0x01;
+    let key = PreimageKey::new(raw_key, PreimageKeyType::Local);
+    assert_eq!(
+        verify(key, boot_info().local_preimage(1).unwrap()),
+        Err(OracleVerifyError::UnknownLocalKey(key))
+    );
+}
@@ -0,0 +1,282 @@
+//! Tests of the zkVM precompiles, differentially against revm where its implementation is always
+//! built.
+
+use alloy_primitives::{hex, Address, Bytes, B256};
+use client_utils::precompiles::{
+    decode_hook_output, encode_hook_output, precompile_preimage, zkvm_precompile,
+    PrecompileHookInput, HOOK_ERROR_UNKNOWN_PRECOMPILE,
+};
+use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use revm::{
+    precompile::{Precompile, PrecompileError, PrecompileErrors, PrecompileOutput, Precompiles},
+    primitives::SpecId,
+};
+use sha2::{Digest, Sha256};
+
+/// Returns the precompile preimage of revm's implementation of the precompile at `address`.
+fn reference_preimage(address: Address, input: &[u8]) -> Vec<u8> {
+    let Some(Precompile::Standard(precompile)) = Precompiles::latest().get(&address) else {
+        panic!("no standard precompile at {}", address);
+    };
+    match precompile(&input.to_vec().into(), u64::MAX) {
+        Ok(output) => [&[0x01][..], output.bytes.as_ref()].concat(),
+        Err(_) => vec![0x00],
+    }
+}
+
+/// Checks that the zkVM precompile returns `expected` for `input`, and that revm agrees.
+fn check(address: u8, input: &[u8], expected: &[u8]) {
+    let address = Address::with_last_byte(address);
+    let preimage = precompile_preimage(address, input).expect("precompile not found");
+    assert_eq!(
+        preimage,
+        reference_preimage(address, input),
+        "differs from revm"
+    );
+    assert_eq!(&preimage[1..], expected);
+}
+
+#[test]
+fn ecrecover_matches_revm() {
+    let valid = hex!("18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c000000000000000000000000000000000000000000000000000000000000001c73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75feeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549");
+    check(
+        1,
+        &valid,
+        &hex!("000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"),
+    );
+
+    // A `v` other than 27 or 28 returns no output.
+    let mut invalid_v = valid;
+    invalid_v[63] = 29;
+    check(1, &invalid_v, &[]);
+
+    // Short inputs are right-padded with zeros, which is an invalid signature.
+    check(1, &valid[..100], &[]);
+    check(1, &[], &[]);
+}
+
+#[test]
+fn sha256_matches_revm() {
+    check(
+        2,
+        &[],
+        &hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+    );
+    check(
+        2,
+        b"abc",
+        &hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    );
+}
+
+#[test]
+fn ripemd160_matches_revm() {
+    check(
+        3,
+        &[],
+        &hex!("0000000000000000000000009c1185a5c5e9fc54612808977ee8f548b2258d31"),
+    );
+}
+
+#[test]
+fn modexp_matches_revm() {
+    // 3^5 mod 7, with one byte lengths.
+    let input = hex!(
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "030507"
+    );
+    check(5, &input, &[0x05]);
+
+    // A zero modulus returns zeros of the modulus length.
+    let input = hex!(
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "03050000"
+    );
+    check(5, &input, &[0x00, 0x00]);
+}
+
+#[test]
+fn point_evaluation_verifies_proofs() {
+    // The zero polynomial, whose commitment and proof are both the point at infinity, evaluates
+    // to zero everywhere.
+    let mut infinity = [0u8; 48];
+    infinity[0] = 0xc0;
+    let mut versioned_hash: [u8; 32] = Sha256::digest(infinity).into();
+    versioned_hash[0] = 0x01;
+    let z = [0x11; 32];
+    let input = [
+        versioned_hash.as_slice(),
+        z.as_slice(),
+        [0u8; 32].as_slice(),
+        infinity.as_slice(),
+        infinity.as_slice(),
+    ]
+    .concat();
+
+    let address = Address::with_last_byte(0x0a);
+    let preimage = precompile_preimage(address, &input).expect("precompile not found");
+    assert_eq!(
+        preimage,
+        hex!(
+            "01"
+            "0000000000000000000000000000000000000000000000000000000000001000"
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        )
+    );
+
+    // A non-zero evaluation of the zero polynomial fails.
+    let mut wrong_y = input.clone();
+    wrong_y[95] = 1;
+    assert_eq!(precompile_preimage(address, &wrong_y), Some(vec![0x00]));
+
+    // A versioned hash that does not match the commitment fails.
+    let mut wrong_hash = input.clone();
+    wrong_hash[0] = 0x02;
+    assert_eq!(precompile_preimage(address, &wrong_hash), Some(vec![0x00]));
+
+    // Inputs of the wrong length fail.
+    assert_eq!(
+        precompile_preimage(address, &input[..191]),
+        Some(vec![0x00])
+    );
+}
+
+#[test]
+fn point_evaluation_verifies_eip4844_vector() {
+    // The `verify_kzg_proof_case_correct_proof_31ebd010e6098750` case of the c-kzg-4844 test
+    // vectors, which revm's implementation is tested with as well.
+    let commitment = hex!("8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7");
+    let z = hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000");
+    let y = hex!("1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e9");
+    let proof = hex!("a62ad71d14c5719385c0686f1871430475bf3a00f0aa3f7b8dd99a9abc2160744faf0070725e00b60ad9a026a15b1a8c");
+    let mut versioned_hash: [u8; 32] = Sha256::digest(commitment).into();
+    versioned_hash[0] = 0x01;
+    let input = [
+        versioned_hash.as_slice(),
+        z.as_slice(),
+        y.as_slice(),
+        commitment.as_slice(),
+        proof.as_slice(),
+    ]
+    .concat();
+
+    let address = Address::with_last_byte(0x0a);
+    assert_eq!(
+        precompile_preimage(address, &input),
+        Some(
+            hex!(
+                "01"
+                "0000000000000000000000000000000000000000000000000000000000001000"
+                "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+            )
+            .to_vec()
+        )
+    );
+
+    // The proof does not hold for any other evaluation.
+    let mut wrong_y = input.clone();
+    wrong_y[95] ^= 1;
+    assert_eq!(precompile_preimage(address, &wrong_y), Some(vec![0x00]));
+}
+
+#[test]
+fn p256_verify_verifies_signatures() {
+    let signing_key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+    let hash = [0x24; 32];
+    let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let input = [
+        hash.as_slice(),
+        signature.to_bytes().as_slice(),
+        &public_key.as_bytes()[1..],
+    ]
+    .concat();
+
+    let address = Address::left_padding_from(&[0x01, 0x00]);
+    let valid = [[0x01].as_slice(), B256::with_last_byte(1).as_slice()].concat();
+    assert_eq!(precompile_preimage(address, &input), Some(valid));
+
+    // An invalid signature succeeds with an empty output.
+    let mut wrong_hash = input.clone();
+    wrong_hash[0] ^= 1;
+    assert_eq!(precompile_preimage(address, &wrong_hash), Some(vec![0x01]));
+
+    // So do inputs of the wrong length.
+    assert_eq!(
+        precompile_preimage(address, &input[..159]),
+        Some(vec![0x01])
+    );
+}
+
+#[test]
+fn hook_input_round_trips() {
+    let input = PrecompileHookInput {
+        address: Address::with_last_byte(0x05),
+        spec_id: SpecId::ECOTONE,
+        gas_limit: 1_000_000,
+        input: Bytes::from_static(&[0x01, 0x02, 0x03]),
+    };
+    let encoded = input.encode();
+    assert_eq!(encoded.len(), 32);
+    assert_eq!(PrecompileHookInput::decode(&encoded), Some(input));
+
+    // An empty call input still round trips.
+    let empty = PrecompileHookInput {
+        input: Bytes::new(),
+        ..PrecompileHookInput::decode(&encoded).unwrap()
+    };
+    assert_eq!(PrecompileHookInput::decode(&empty.encode()), Some(empty));
+
+    // Short buffers and unknown spec ids are rejected.
+    assert_eq!(PrecompileHookInput::decode(&encoded[..28]), None);
+    let mut unknown_spec = encoded.clone();
+    unknown_spec[20] = 0xFE;
+    assert_eq!(PrecompileHookInput::decode(&unknown_spec), None);
+}
+
+#[test]
+fn hook_output_round_trips() {
+    let output = PrecompileOutput::new(3_450, Bytes::from_static(&[0xAB; 32]));
+    let decoded = decode_hook_output(&encode_hook_output(&Ok(output.clone()))).unwrap();
+    assert_eq!(decoded.gas_used, output.gas_used);
+    assert_eq!(decoded.bytes, output.bytes);
+
+    let error = encode_hook_output(&Err(PrecompileError::OutOfGas.into()));
+    assert!(matches!(
+        decode_hook_output(&error),
+        Err(PrecompileErrors::Error(_))
+    ));
+    let fatal = encode_hook_output(&Err(PrecompileErrors::Fatal {
+        msg: "fatal".to_string(),
+    }));
+    assert!(matches!(
+        decode_hook_output(&fatal),
+        Err(PrecompileErrors::Fatal { .. })
+    ));
+
+    // The host's own failures and malformed outputs are fatal.
+    assert!(matches!(
+        decode_hook_output(&[1, HOOK_ERROR_UNKNOWN_PRECOMPILE]),
+        Err(PrecompileErrors::Fatal { .. })
+    ));
+    assert!(matches!(
+        decode_hook_output(&[0, 1, 2]),
+        Err(PrecompileErrors::Fatal { .. })
+    ));
+}
+
+#[test]
+fn zkvm_precompile_follows_the_spec() {
+    let point_evaluation = Address::with_last_byte(0x0a);
+    assert!(zkvm_precompile(SpecId::REGOLITH, &point_evaluation).is_none());
+    assert!(zkvm_precompile(SpecId::ECOTONE, &point_evaluation).is_some());
+
+    // `P256VERIFY` is added by Fjord, which revm's precompile sets do not include.
+    let p256_verify = Address::left_padding_from(&[0x01, 0x00]);
+    assert!(zkvm_precompile(SpecId::ECOTONE, &p256_verify).is_none());
+    assert!(zkvm_precompile(SpecId::FJORD, &p256_verify).is_some());
+}
@@ -0,0 +1,52 @@
+//! Tests of the public values the programs commit to, against the fixture the contracts are
+//! tested with.
+
+use alloy_primitives::{b256, hex, U256};
+use client_utils::{public_values_version, RawBootInfo, BOOT_INFO_SIZE, BOOT_INFO_VERSION};
+
+/// `RUST_ENCODING` in `contracts/test/PublicValues.t.sol`, which checks that the contract's
+/// `PublicValuesStruct` encodes to the same bytes.
+const SOLIDITY_ENCODING: [u8; BOOT_INFO_SIZE] = hex!("0000000000000000000000000000000000000000000000000000000000000002648a03238b239b1afa20953c99812a0640fec18776b07af8b8c0cdbceacf2dc15de5bc23543212acfe6d68ccb2f243c80cbd3765782f424def4b1e68babcfe940000000000000000000000000000000000000000000000000000000000f3740d48a9017aba4e1e3fcdcf9e792fd2b749f9852fa7cf677021033e39fa2e2608eb0000000000000000000000000000000000000000000000000000000000f376510000000000000000000000000000000000000000000000000000000000aa37dcc0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ff");
+
+/// The values `SOLIDITY_ENCODING` is the encoding of.
+fn fixture() -> RawBootInfo {
+    RawBootInfo {
+        l1_head: b256!("648a03238b239b1afa20953c99812a0640fec18776b07af8b8c0cdbceacf2dc1"),
+        l2_output_root: b256!("5DE5BC23543212ACFE6D68CCB2F243C80CBD3765782F424DEF4B1E68BABCFE94"),
+        l2_output_root_block: 15954957,
+        l2_claim: b256!("48A9017ABA4E1E3FCDCF9E792FD2B749F9852FA7CF677021033E39FA2E2608EB"),
+        l2_claim_block: 15955537,
+        chain_id: 11155420,
+        rollup_config_hash: b256!(
+            "c0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ffeec0ff"
+        ),
+        rollup_config: None,
+    }
+}
+
+#[test]
+fn encoding_matches_solidity() {
+    assert_eq!(fixture().abi_encode(), SOLIDITY_ENCODING);
+    assert_eq!(
+        public_values_version(&SOLIDITY_ENCODING).unwrap(),
+        U256::from(BOOT_INFO_VERSION)
+    );
+}
+
+#[test]
+fn decoding_round_trips() {
+    let decoded = RawBootInfo::abi_decode(&SOLIDITY_ENCODING).unwrap();
+    assert_eq!(decoded.abi_encode(), SOLIDITY_ENCODING);
+    assert_eq!(decoded.l2_claim_block, fixture().l2_claim_block);
+    assert_eq!(decoded.rollup_config_hash, fixture().rollup_config_hash);
+}
+
+#[test]
+fn decoding_rejects_other_layouts() {
+    let mut other_version = SOLIDITY_ENCODING;
+    other_version[31] = 1;
+    assert!(RawBootInfo::abi_decode(&other_version).is_err());
+
+    assert!(RawBootInfo::abi_decode(&SOLIDITY_ENCODING[..BOOT_INFO_SIZE - 32]).is_err());
+    assert!(RawBootInfo::abi_decode(&[0u8; 16]).is_err());
+}
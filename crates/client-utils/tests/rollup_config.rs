@@ -0,0 +1,75 @@
+//! Tests of the canonical encoding of the rollup config that the boot info commits to the hash of.
+
+use alloy_eips::eip1559::BaseFeeParams;
+use alloy_primitives::{b256, Address, B256, U256};
+use client_utils::{hash_rollup_config, ROLLUP_CONFIG_HASH_VERSION};
+use kona_primitives::{BlockID, ChainGenesis, RollupConfig, SystemConfig};
+
+/// A rollup config with every hashed field set to a distinct value, and a mix of set and unset
+/// optional fields.
+fn rollup_config() -> RollupConfig {
+    RollupConfig {
+        genesis: ChainGenesis {
+            l1: BlockID {
+                hash: B256::repeat_byte(0x11),
+                number: 1,
+            },
+            l2: BlockID {
+                hash: B256::repeat_byte(0x22),
+                number: 2,
+            },
+            l2_time: 3,
+            system_config: Some(SystemConfig {
+                batcher_address: Address::repeat_byte(0x33),
+                overhead: U256::from(4),
+                scalar: U256::from(5),
+                gas_limit: 6,
+                base_fee_scalar: Some(7),
+                blob_base_fee_scalar: None,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        block_time: 8,
+        max_sequencer_drift: 9,
+        seq_window_size: 10,
+        channel_timeout: 11,
+        l1_chain_id: 12,
+        l2_chain_id: 13,
+        base_fee_params: BaseFeeParams::new(50, 6),
+        canyon_base_fee_params: BaseFeeParams::new(250, 6),
+        regolith_time: Some(14),
+        canyon_time: Some(15),
+        delta_time: None,
+        ecotone_time: Some(16),
+        fjord_time: None,
+        batch_inbox_address: Address::repeat_byte(0x44),
+        deposit_contract_address: Address::repeat_byte(0x55),
+        l1_system_config_address: Address::repeat_byte(0x66),
+        protocol_versions_address: Address::repeat_byte(0x77),
+        superchain_config_address: Some(Address::repeat_byte(0x88)),
+        blobs_enabled_l1_timestamp: None,
+        da_challenge_address: Some(Address::repeat_byte(0x99)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rollup_config_hash_matches_the_pinned_vector() {
+    // Changing this vector changes the hash that deployed oracles are configured with, so it must
+    // come with a bump of the encoding version.
+    assert_eq!(ROLLUP_CONFIG_HASH_VERSION, 1);
+    assert_eq!(
+        hash_rollup_config(&rollup_config()),
+        b256!("dec9d692c748ff1b92bbfb021fd3f5cd47402ed0a01ca9a3cc251d1540224462")
+    );
+}
+
+#[test]
+fn rollup_config_hash_distinguishes_unset_from_zero() {
+    let mut rollup_config = rollup_config();
+    let hash = hash_rollup_config(&rollup_config);
+
+    rollup_config.delta_time = Some(0);
+    assert_ne!(hash_rollup_config(&rollup_config), hash);
+}
@@ -0,0 +1,135 @@
+//! Fetches the inputs of alt-DA commitments from a DA server into the data directory.
+//!
+//! The native client hints each alt-DA input it reads with [ALTDA_COMMITMENT_HINT], but kona-host
+//! only fetches the data of the hints it knows. So before native execution, the host scans the
+//! batcher transactions in the L1 blocks that derivation may read, and writes the input of every
+//! keccak256 commitment to the data directory, where kona-host serves preimages from.
+//!
+//! [ALTDA_COMMITMENT_HINT]: client_utils::altda::ALTDA_COMMITMENT_HINT
+
+use alloy::{providers::Provider, transports::http::reqwest};
+use alloy_primitives::{hex, keccak256, B256};
+use anyhow::{anyhow, Result};
+use client_utils::altda::AltDAData;
+use kona_host::HostCli;
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use log::warn;
+use std::env;
+
+use crate::{
+    fetcher::{ChainMode, SP1KonaDataFetcher},
+    get_rollup_config,
+    helpers::write_preimage,
+};
+
+/// The environment variable holding the URL of the DA server of chains that use alt-DA.
+pub const ALTDA_SERVER_URL_ENV: &str = "ALTDA_SERVER_URL";
+
+/// The number of seconds between L1 blocks.
+const L1_BLOCK_TIME: u64 = 12;
+
+/// Fetches the input of every keccak256 commitment posted to the batch inbox between the L1
+/// origin of the agreed L2 head, less the channel timeout, and the L1 head, and writes it to the
+/// data directory. Does nothing for chains that do not use alt-DA. Returns the number of inputs
+/// fetched.
+///
+/// A commitment whose input the DA server does not have is skipped with a warning, as anyone can
+/// post to the batch inbox. If derivation does need its input, native execution fails on it.
+pub async fn fetch_altda_inputs(
+    data_fetcher: &SP1KonaDataFetcher,
+    host_cli: &HostCli,
+) -> Result<usize> {
+    let rollup_config = get_rollup_config(host_cli)?;
+    if rollup_config.da_challenge_address.is_none() {
+        return Ok(0);
+    }
+    let server_url = env::var(ALTDA_SERVER_URL_ENV).map_err(|_| {
+        anyhow!(
+            "chain {} uses alt-DA, set {} to its DA server",
+            host_cli.l2_chain_id,
+            ALTDA_SERVER_URL_ENV
+        )
+    })?;
+    let data_dir = host_cli
+        .data_dir
+        .as_ref()
+        .ok_or_else(|| anyhow!("data directory not set"))?;
+
+    // The L1 origin of the agreed L2 head is at most the sequencer drift behind it, and its
+    // channels may have been opened up to the channel timeout before that.
+    let l2_head = data_fetcher
+        .get_header_by_hash(ChainMode::L2, host_cli.l2_head)
+        .await?;
+    let start_timestamp = l2_head.timestamp.saturating_sub(
+        rollup_config.max_sequencer_drift + rollup_config.channel_timeout * L1_BLOCK_TIME,
+    );
+    let start = data_fetcher
+        .get_header_by_hash(
+            ChainMode::L1,
+            data_fetcher
+                .find_block_by_timestamp(ChainMode::L1, start_timestamp)
+                .await?,
+        )
+        .await?
+        .number;
+    let end = data_fetcher
+        .get_header_by_hash(ChainMode::L1, host_cli.l1_head)
+        .await?
+        .number;
+
+    let l1_provider = data_fetcher.get_provider(ChainMode::L1);
+    let mut fetched = 0;
+    for number in start..=end {
+        let block = l1_provider
+            .get_block_by_number(number.into(), true)
+            .await?
+            .ok_or_else(|| anyhow!("L1 block {} not found", number))?;
+        let Some(transactions) = block.transactions.as_transactions() else {
+            continue;
+        };
+
+        for tx in transactions {
+            if tx.to != Some(rollup_config.batch_inbox_address) {
+                continue;
+            }
+            let AltDAData::Keccak(commitment) = AltDAData::decode(tx.input.clone()) else {
+                continue;
+            };
+            match fetch_input(&server_url, commitment).await {
+                Ok(input) => {
+                    let key = PreimageKey::new(*commitment, PreimageKeyType::Keccak256);
+                    write_preimage(data_dir, key.into(), &input)?;
+                    fetched += 1;
+                }
+                Err(e) => warn!(
+                    "Skipping alt-DA commitment {} in L1 block {}: {}",
+                    commitment, number, e
+                ),
+            }
+        }
+    }
+
+    Ok(fetched)
+}
+
+/// Fetches the input of a keccak256 commitment from the DA server, and checks it against the
+/// commitment.
+async fn fetch_input(server_url: &str, commitment: B256) -> Result<Vec<u8>> {
+    // The DA server is queried with the commitment type, which is 0x00 for keccak256, and the
+    // commitment.
+    let url = format!(
+        "{}/get/0x00{}",
+        server_url.trim_end_matches('/'),
+        hex::encode(commitment)
+    );
+    let input = reqwest::get(&url)
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec();
+    if keccak256(&input) != commitment {
+        return Err(anyhow!("DA server returned an input that does not match"));
+    }
+    Ok(input)
+}
@@ -140,7 +140,7 @@ impl SP1KonaDataFetcher {
     }
 
     /// Find the block with the closest timestamp to the target timestamp.
-    async fn find_block_by_timestamp(
+    pub(crate) async fn find_block_by_timestamp(
         &self,
         chain_mode: ChainMode,
         target_timestamp: u64,
@@ -1,46 +1,157 @@
 use alloy_primitives::hex;
-use client_utils::BytesHasherBuilder;
-use std::{collections::HashMap, fs, io::Read, path::PathBuf};
+use anyhow::{anyhow, Result};
+use client_utils::{BytesHasherBuilder, KvStore, OracleKey};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use std::{
+    collections::HashSet,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-pub fn load_kv_store(data_dir: &PathBuf) -> HashMap<[u8; 32], Vec<u8>, BytesHasherBuilder> {
-    let capacity = get_file_count(data_dir);
-    let mut cache: HashMap<[u8; 32], Vec<u8>, BytesHasherBuilder> =
-        HashMap::with_capacity_and_hasher(capacity, BytesHasherBuilder);
+/// The name of the file in the data directory that the native client writes the keys of all
+/// preimages it read to.
+const ACCESS_LOG_FILE: &str = "access.log";
+
+/// The name of the file in the data directory that the native client writes the derivation trace
+/// to.
+const DERIVATION_TRACE_FILE: &str = "derivation_trace.jsonl";
+
+/// Loads the preimages that native execution wrote to the data directory. Files whose names are
+/// not hex are skipped, and a hex name that is not a valid preimage key is an error.
+pub fn load_kv_store(data_dir: &PathBuf) -> Result<KvStore> {
+    let capacity = get_file_count(data_dir)?;
+    let mut cache: KvStore = KvStore::with_capacity_and_hasher(capacity, BytesHasherBuilder);
 
     // Iterate over the files in the 'data' directory
-    for entry in fs::read_dir(data_dir)
-        .expect("Failed to read data directory")
-        .flatten()
-    {
-        let path = entry.path();
+    for entry in fs::read_dir(data_dir)? {
+        let path = entry?.path();
         if path.is_file() {
             // Extract the file name
-            let file_name = path.file_stem().unwrap().to_str().unwrap();
+            let Some(file_name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
 
             // Convert the file name to PreimageKey
             if let Ok(key) = hex::decode(file_name) {
                 // Read the file contents
-                let mut file = fs::File::open(path).expect("Failed to open file");
+                let mut file = fs::File::open(&path)?;
                 let mut contents = Vec::new();
-                file.read_to_end(&mut contents)
-                    .expect("Failed to read file");
+                file.read_to_end(&mut contents)?;
 
                 // Insert the key-value pair into the cache
-                cache.insert(key.try_into().unwrap(), contents);
+                let key: [u8; 32] = key
+                    .try_into()
+                    .map_err(|_| anyhow!("invalid preimage key length: {}", path.display()))?;
+                let key = OracleKey::try_from(key)
+                    .map_err(|e| anyhow!("{} in data directory: {}", e, path.display()))?;
+                cache.insert(key, contents);
             }
         }
     }
 
-    cache
+    Ok(cache)
+}
+
+/// Writes a preimage to the data directory, in the layout of kona-host's disk key value store.
+pub fn write_preimage(data_dir: &Path, key: OracleKey, value: &[u8]) -> Result<()> {
+    fs::write(
+        data_dir.join(format!("{}.bin", hex::encode(key.as_bytes()))),
+        value,
+    )?;
+    Ok(())
 }
 
-fn get_file_count(data_dir: &PathBuf) -> usize {
+fn get_file_count(data_dir: &PathBuf) -> Result<usize> {
     let mut file_count = 0;
-    for entry in fs::read_dir(data_dir).expect("failed to read data dir") {
-        let entry = entry.unwrap();
-        if entry.metadata().unwrap().is_file() {
+    for entry in fs::read_dir(data_dir)? {
+        if entry?.metadata()?.is_file() {
             file_count += 1;
         }
     }
-    file_count
+    Ok(file_count)
+}
+
+/// Returns the path of the access log in the data directory.
+pub fn access_log_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(ACCESS_LOG_FILE)
+}
+
+/// Returns the path of the derivation trace in the data directory.
+pub fn derivation_trace_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(DERIVATION_TRACE_FILE)
+}
+
+/// Loads the keys of all preimages read by the native client, or `None` if the client did not
+/// write an access log to the data directory.
+pub fn load_access_log(data_dir: &Path) -> Result<Option<HashSet<OracleKey>>> {
+    let path = access_log_path(data_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(path)?
+        .lines()
+        .map(|line| {
+            let key: [u8; 32] = hex::decode(line)?
+                .try_into()
+                .map_err(|_| anyhow!("invalid key in access log: {}", line))?;
+            Ok(OracleKey::try_from(key)?)
+        })
+        .collect::<Result<HashSet<OracleKey>>>()
+        .map(Some)
+}
+
+/// Removes all preimages that were not read by the native client from the key value store, and
+/// returns the number of preimages and bytes removed.
+///
+/// Preimages that the zkVM needs to verify the preimages that were read are kept: local keys,
+/// the keccak256 preimages of blob and precompile keys, and every element of a blob that was
+/// partially read, as blobs are verified as a whole.
+pub fn prune_kv_store(kv_store: &mut KvStore, accessed: &HashSet<OracleKey>) -> (usize, usize) {
+    let mut keep = accessed.clone();
+    let mut commitments: HashSet<[u8; 48]> = HashSet::new();
+    for key in accessed {
+        if matches!(
+            key.key_type(),
+            PreimageKeyType::Blob | PreimageKeyType::Precompile
+        ) {
+            let preimage_key: OracleKey =
+                PreimageKey::new(*key.as_bytes(), PreimageKeyType::Keccak256).into();
+            keep.insert(preimage_key);
+
+            // The blob key preimage is `abi.encodePacked(commitment, uint256(element))`.
+            if key.key_type() == PreimageKeyType::Blob {
+                if let Some(blob_data) = kv_store.get(&preimage_key).filter(|d| d.len() == 80) {
+                    commitments.insert(blob_data[..48].try_into().unwrap());
+                }
+            }
+        }
+    }
+
+    // Keep the remaining elements of every blob that was read, along with their preimages.
+    if !commitments.is_empty() {
+        for (key, value) in kv_store.iter() {
+            if key.key_type() != PreimageKeyType::Keccak256 || value.len() != 80 {
+                continue;
+            }
+            let commitment: [u8; 48] = value[..48].try_into().unwrap();
+            if commitments.contains(&commitment) {
+                keep.insert(*key);
+                keep.insert(PreimageKey::new(*key.as_bytes(), PreimageKeyType::Blob).into());
+            }
+        }
+    }
+
+    let (mut removed_entries, mut removed_bytes) = (0, 0);
+    kv_store.retain(|key, value| {
+        if key.key_type() == PreimageKeyType::Local || keep.contains(key) {
+            return true;
+        }
+        removed_entries += 1;
+        removed_bytes += key.as_bytes().len() + value.len();
+        false
+    });
+
+    (removed_entries, removed_bytes)
 }
@@ -1,25 +1,39 @@
+pub mod altda;
 pub mod fetcher;
 pub mod helpers;
+pub mod native;
+pub mod witness;
 
 use alloy_consensus::Header;
 use alloy_primitives::B256;
-use client_utils::{types::AggregationInputs, RawBootInfo};
+use client_utils::{
+    hash_rollup_config, load_rollup_config, types::AggregationInputs, InMemoryOracle, KvStore,
+    RawBootInfo, WitnessEncoding, ROLLUP_CONFIG_PATH_ENV, STDIN_VERSION,
+};
 use kona_host::HostCli;
+use kona_primitives::RollupConfig;
 use sp1_sdk::{SP1Proof, SP1Stdin};
+use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use alloy_sol_types::sol;
 
 use rkyv::{
     ser::{
-        serializers::{AlignedSerializer, CompositeSerializer, HeapScratch, SharedSerializeMap},
+        serializers::{
+            AlignedSerializer, AllocScratch, CompositeSerializer, FallbackScratch, HeapScratch,
+            SharedSerializeMap,
+        },
         Serializer,
     },
     AlignedVec,
 };
 
-use crate::helpers::load_kv_store;
+use crate::{
+    helpers::{load_access_log, load_kv_store, prune_kv_store},
+    witness::{witness_archive_path, WitnessArchive},
+};
 
 pub enum ProgramType {
     Single,
@@ -35,34 +49,167 @@ sol! {
     }
 }
 
-/// Get the stdin to generate a proof for the given L2 claim.
-pub fn get_proof_stdin(host_cli: &HostCli) -> Result<SP1Stdin> {
-    let mut stdin = SP1Stdin::new();
+/// The size of the fixed scratch space used when serializing the key value store. Serialization
+/// falls back to allocating once it is used up.
+const SCRATCH_SPACE_SIZE: usize = 1 << 20;
+
+/// The number of bytes each entry of the archived key value store takes in addition to its value:
+/// the 32 byte key, the relative pointer and length of the value, the hash index entry, and
+/// padding.
+const ARCHIVED_ENTRY_OVERHEAD: usize = 64;
+
+/// Estimates the size of the archived key value store, so the output buffer rarely needs to grow.
+fn estimate_archived_size(kv_store: &KvStore) -> usize {
+    kv_store
+        .values()
+        .map(|value| value.len() + ARCHIVED_ENTRY_OVERHEAD)
+        .sum()
+}
+
+/// Returns the rollup config to prove with: the one supplied at [ROLLUP_CONFIG_PATH_ENV], if any,
+/// or the one registered for the chain id.
+pub fn get_rollup_config(host_cli: &HostCli) -> Result<RollupConfig> {
+    resolve_rollup_config(host_cli, load_rollup_config()?.as_ref())
+}
+
+/// Returns the supplied rollup config, or the one registered for the chain id if there is none,
+/// and checks that it is for the chain id.
+fn resolve_rollup_config(
+    host_cli: &HostCli,
+    supplied_rollup_config: Option<&RollupConfig>,
+) -> Result<RollupConfig> {
+    let rollup_config = match supplied_rollup_config {
+        Some(rollup_config) => rollup_config.clone(),
+        None => RollupConfig::from_l2_chain_id(host_cli.l2_chain_id).ok_or_else(|| {
+            anyhow!(
+                "no rollup config registered for chain id {}, set {}",
+                host_cli.l2_chain_id,
+                ROLLUP_CONFIG_PATH_ENV
+            )
+        })?,
+    };
+    if rollup_config.l2_chain_id != host_cli.l2_chain_id {
+        return Err(anyhow!(
+            "rollup config is for chain id {}, not {}",
+            rollup_config.l2_chain_id,
+            host_cli.l2_chain_id
+        ));
+    }
+    Ok(rollup_config)
+}
+
+/// Get the boot info for the given L2 claim, committing to the supplied rollup config or the one
+/// registered for the chain id.
+fn get_boot_info(host_cli: &HostCli) -> Result<RawBootInfo> {
+    let supplied_rollup_config = load_rollup_config()?;
+    let rollup_config = resolve_rollup_config(host_cli, supplied_rollup_config.as_ref())?;
 
-    let boot_info = RawBootInfo {
+    Ok(RawBootInfo {
         l1_head: host_cli.l1_head,
         l2_output_root: host_cli.l2_output_root,
+        // Derived from the output root by the client.
+        l2_output_root_block: 0,
         l2_claim: host_cli.l2_claim,
         l2_claim_block: host_cli.l2_block_number,
         chain_id: host_cli.l2_chain_id,
-    };
+        rollup_config_hash: hash_rollup_config(&rollup_config),
+        rollup_config: supplied_rollup_config,
+    })
+}
+
+/// Packs the data directory written by native execution into a witness archive next to it, and
+/// returns the path of the archive.
+pub fn write_witness_archive(host_cli: &HostCli) -> Result<PathBuf> {
+    let data_dir = host_cli.data_dir.as_ref().expect("Data directory not set!");
+    let path = witness_archive_path(data_dir);
+    WitnessArchive::from_data_dir(data_dir, get_boot_info(host_cli)?)?.write(&path)?;
+    Ok(path)
+}
+
+/// Get the stdin to generate a proof for the given L2 claim, with the key value store encoded
+/// with the given encoding.
+pub fn get_proof_stdin(host_cli: &HostCli, encoding: WitnessEncoding) -> Result<SP1Stdin> {
+    let mut stdin = SP1Stdin::new();
+
+    let boot_info = get_boot_info(host_cli)?;
+    stdin.write(&STDIN_VERSION);
     stdin.write(&boot_info);
 
     // Get the workspace root, which is where the data directory is.
     let data_dir = host_cli.data_dir.as_ref().expect("Data directory not set!");
-    let kv_store = load_kv_store(data_dir);
 
+    // Prefer the packed witness archive, and fall back to the data directory if there is none.
+    let archive_path = witness_archive_path(data_dir);
+    let mut kv_store = if archive_path.exists() {
+        let archive = WitnessArchive::read(&archive_path)?;
+        if archive.boot_info.abi_encode() != boot_info.abi_encode() {
+            return Err(anyhow!(
+                "witness archive {} was generated for a different claim",
+                archive_path.display()
+            ));
+        }
+        archive.kv_store
+    } else {
+        load_kv_store(data_dir)?
+    };
+
+    // Drop the preimages that the native client never read, if it recorded which ones it did.
+    if let Some(accessed) = load_access_log(data_dir)? {
+        let total_entries = kv_store.len();
+        let (removed_entries, removed_bytes) = prune_kv_store(&mut kv_store, &accessed);
+        println!(
+            "Pruned {} of {} preimages from the witness, saving {} bytes.",
+            removed_entries, total_entries, removed_bytes
+        );
+    }
+
+    // Run the same verification as the zkVM natively, so that a bad witness is reported before
+    // paying for execution.
+    let oracle = InMemoryOracle::from_kv_store(kv_store);
+    oracle
+        .verify(&boot_info)
+        .map_err(|e| anyhow!("witness verification failed: {}", e))?;
+    let kv_store = oracle.into_kv_store();
+
+    // Presize the output buffer from the loaded store, and fall back to allocating scratch space
+    // once the fixed scratch space is used up, so that large ranges can always be serialized.
     let mut serializer = CompositeSerializer::new(
-        AlignedSerializer::new(AlignedVec::new()),
-        // TODO: This value corresponds to the size of the space needed to
-        // serialize the KV store. We should compute this dynamically.
-        HeapScratch::<33554432>::new(),
+        AlignedSerializer::new(AlignedVec::with_capacity(estimate_archived_size(&kv_store))),
+        FallbackScratch::new(
+            HeapScratch::<SCRATCH_SPACE_SIZE>::new(),
+            AllocScratch::new(),
+        ),
         SharedSerializeMap::new(),
     );
-    serializer.serialize_value(&kv_store)?;
+    serializer.serialize_value(&kv_store).map_err(|e| {
+        anyhow!(
+            "failed to serialize the key value store of {} preimages: {}",
+            kv_store.len(),
+            e
+        )
+    })?;
 
     let buffer = serializer.into_serializer().into_inner();
     let kv_store_bytes = buffer.into_vec();
+    println!(
+        "Serialized {} preimages into {} bytes.",
+        kv_store.len(),
+        kv_store_bytes.len()
+    );
+
+    let raw_size = kv_store_bytes.len();
+    let kv_store_bytes = encoding.encode(kv_store_bytes);
+    if encoding != WitnessEncoding::Raw {
+        println!(
+            "Encoded the key value store with {:?}: {} bytes ({:.1}% of {} raw bytes).",
+            encoding,
+            kv_store_bytes.len(),
+            kv_store_bytes.len() as f64 * 100.0 / raw_size as f64,
+            raw_size
+        );
+    }
+
+    stdin.write(&encoding);
     stdin.write_slice(&kv_store_bytes);
 
     Ok(stdin)
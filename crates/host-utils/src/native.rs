@@ -0,0 +1,109 @@
+//! Runs the native client to generate the witness for a proof.
+//!
+//! The native client is run under `native-host` in a child process, rather than with kona's
+//! `start_server_and_native_client` in this process, so that the paths the client writes to are
+//! handed to that run's processes alone instead of through this process's environment, which
+//! concurrent runs share.
+
+use std::{env, ffi::OsString, fs, path::Path};
+
+use anyhow::{anyhow, Result};
+use client_utils::{trace::DERIVATION_TRACE_ENV, ACCESS_LOG_ENV, ROLLUP_CONFIG_PATH_ENV};
+use kona_host::HostCli;
+use tokio::process::Command;
+
+use crate::helpers::{access_log_path, derivation_trace_path};
+
+/// The name of the `native-host` binary, which is built next to the native clients.
+const NATIVE_HOST_BIN: &str = "native-host";
+
+/// Runs the native client in `host_cli.exec` against the data directory, and has it write the
+/// keys of all preimages it read to the access log in the data directory. With `trace`, the
+/// client also writes a trace of how it derived each block to the data directory.
+///
+/// The client generates the witness with the rollup config supplied at [ROLLUP_CONFIG_PATH_ENV], if
+/// any, which is the one that is proven with.
+pub async fn run_native_client(host_cli: &HostCli, trace: bool) -> Result<()> {
+    let data_dir = host_cli
+        .data_dir
+        .as_ref()
+        .ok_or_else(|| anyhow!("data directory not set"))?;
+    let exec = host_cli
+        .exec
+        .as_ref()
+        .ok_or_else(|| anyhow!("native client binary not set"))?;
+
+    // The client appends to the access log, so remove any log left over from a previous run.
+    let access_log = access_log_path(data_dir);
+    remove_stale(&access_log)?;
+
+    let mut command = Command::new(Path::new(exec).with_file_name(NATIVE_HOST_BIN));
+    command
+        .args(native_host_args(host_cli))
+        .env(ACCESS_LOG_ENV, &access_log);
+
+    // Remove any trace left over from a previous run, so a run that fails before deriving a
+    // block does not leave a stale trace behind.
+    let derivation_trace = derivation_trace_path(data_dir);
+    remove_stale(&derivation_trace)?;
+    if trace {
+        command.env(DERIVATION_TRACE_ENV, &derivation_trace);
+    } else {
+        command.env_remove(DERIVATION_TRACE_ENV);
+    }
+
+    match env::var_os(ROLLUP_CONFIG_PATH_ENV) {
+        Some(path) => command.env(ROLLUP_CONFIG_PATH_ENV, path),
+        None => command.env_remove(ROLLUP_CONFIG_PATH_ENV),
+    };
+
+    let status = command.status().await?;
+    if !status.success() {
+        return Err(anyhow!("native execution failed: {}", status));
+    }
+    Ok(())
+}
+
+/// Removes the file at `path` if it exists.
+fn remove_stale(path: &Path) -> Result<()> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Returns the command line of `native-host` for the given arguments.
+fn native_host_args(host_cli: &HostCli) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![
+        "--l1-head".into(),
+        host_cli.l1_head.to_string().into(),
+        "--l2-head".into(),
+        host_cli.l2_head.to_string().into(),
+        "--l2-output-root".into(),
+        host_cli.l2_output_root.to_string().into(),
+        "--l2-claim".into(),
+        host_cli.l2_claim.to_string().into(),
+        "--l2-block-number".into(),
+        host_cli.l2_block_number.to_string().into(),
+        "--l2-chain-id".into(),
+        host_cli.l2_chain_id.to_string().into(),
+    ];
+    let optional = [
+        ("--l1-node-address", host_cli.l1_node_address.clone()),
+        ("--l1-beacon-address", host_cli.l1_beacon_address.clone()),
+        ("--l2-node-address", host_cli.l2_node_address.clone()),
+        ("--exec", host_cli.exec.clone()),
+    ];
+    for (flag, value) in optional {
+        if let Some(value) = value {
+            args.extend([flag.into(), value.into()]);
+        }
+    }
+    if let Some(data_dir) = &host_cli.data_dir {
+        args.extend(["--data-dir".into(), data_dir.into()]);
+    }
+    if host_cli.v > 0 {
+        args.push(format!("-{}", "v".repeat(host_cli.v as usize)).into());
+    }
+    args
+}
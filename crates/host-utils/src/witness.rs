@@ -0,0 +1,167 @@
+//! A packed, single-file archive of the witness produced by native execution.
+//!
+//! Layout (all integers little-endian):
+//! - magic `b"OPWA"` and a `u32` format version
+//! - `u64` chain id
+//! - `u32` length of the ABI encoded boot info, followed by the boot info
+//! - `u32` length of the JSON encoded rollup config supplied by the host, followed by the rollup
+//!   config, or `0` if the rollup config is looked up in kona's registry
+//! - `u64` record count, followed by the records, each a 32 byte preimage key, a `u32` value
+//!   length and the value
+//! - keccak256 checksum of everything before it
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::{keccak256, B256};
+use anyhow::{anyhow, bail, Result};
+use client_utils::{hash_rollup_config, KvStore, OracleKey, RawBootInfo};
+
+use crate::helpers::load_kv_store;
+
+/// The magic bytes at the start of every witness archive.
+const MAGIC: [u8; 4] = *b"OPWA";
+
+/// The current version of the witness archive format.
+const VERSION: u32 = 2;
+
+/// The witness for a proof: the boot info and every preimage the client may read.
+pub struct WitnessArchive {
+    /// The L2 chain id the witness was generated for.
+    pub chain_id: u64,
+    /// The boot info the witness was generated for, including the rollup config supplied by the
+    /// host, if any.
+    pub boot_info: RawBootInfo,
+    /// The preimages of the witness.
+    pub kv_store: KvStore,
+}
+
+/// Returns the path of the witness archive for the given data directory, which sits next to it.
+pub fn witness_archive_path(data_dir: &Path) -> PathBuf {
+    data_dir.with_extension("witness")
+}
+
+impl WitnessArchive {
+    /// Packs the one-file-per-preimage data directory written by kona-host into an archive.
+    pub fn from_data_dir(data_dir: &PathBuf, boot_info: RawBootInfo) -> Result<Self> {
+        Ok(Self {
+            chain_id: boot_info.chain_id,
+            boot_info,
+            kv_store: load_kv_store(data_dir)?,
+        })
+    }
+
+    /// Writes the archive to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let boot_info = self.boot_info.abi_encode();
+        let rollup_config = match &self.boot_info.rollup_config {
+            Some(rollup_config) => serde_json::to_vec(rollup_config)?,
+            None => Vec::new(),
+        };
+        let values_size: usize = self.kv_store.values().map(Vec::len).sum();
+        let mut buf = Vec::with_capacity(
+            64 + boot_info.len() + rollup_config.len() + self.kv_store.len() * 36 + values_size,
+        );
+
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&VERSION.to_le_bytes());
+        buf.extend_from_slice(&self.chain_id.to_le_bytes());
+        buf.extend_from_slice(&(boot_info.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&boot_info);
+        buf.extend_from_slice(&(rollup_config.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&rollup_config);
+
+        buf.extend_from_slice(&(self.kv_store.len() as u64).to_le_bytes());
+        for (key, value) in self.kv_store.iter() {
+            buf.extend_from_slice(key.as_bytes());
+            buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            buf.extend_from_slice(value);
+        }
+
+        let checksum = keccak256(&buf);
+        buf.extend_from_slice(checksum.as_slice());
+
+        fs::write(path, buf)?;
+        Ok(())
+    }
+
+    /// Reads an archive from the given path with a single sequential read, and checks its
+    /// checksum.
+    pub fn read(path: &Path) -> Result<Self> {
+        let buf = fs::read(path)?;
+        if buf.len() < 32 {
+            bail!("witness archive {} is truncated", path.display());
+        }
+        let (body, checksum) = buf.split_at(buf.len() - 32);
+        if keccak256(body) != B256::from_slice(checksum) {
+            bail!("witness archive {} has a bad checksum", path.display());
+        }
+
+        let mut reader = Reader { buf: body };
+        if reader.take(4)? != MAGIC {
+            bail!("{} is not a witness archive", path.display());
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            bail!("unsupported witness archive version {}", version);
+        }
+        let chain_id = reader.u64()?;
+        let boot_info_len = reader.u32()? as usize;
+        let mut boot_info = RawBootInfo::abi_decode(reader.take(boot_info_len)?)?;
+        let rollup_config_len = reader.u32()? as usize;
+        if rollup_config_len > 0 {
+            let rollup_config = serde_json::from_slice(reader.take(rollup_config_len)?)?;
+            if hash_rollup_config(&rollup_config) != boot_info.rollup_config_hash {
+                bail!(
+                    "witness archive {} has a rollup config that does not match its boot info",
+                    path.display()
+                );
+            }
+            boot_info.rollup_config = Some(rollup_config);
+        }
+
+        let count = reader.u64()? as usize;
+        let mut kv_store = KvStore::with_capacity_and_hasher(count, Default::default());
+        for _ in 0..count {
+            let key: [u8; 32] = reader.take(32)?.try_into().unwrap();
+            let key = OracleKey::try_from(key)?;
+            let value_len = reader.u32()? as usize;
+            kv_store.insert(key, reader.take(value_len)?.to_vec());
+        }
+        if !reader.buf.is_empty() {
+            bail!("witness archive {} has trailing data", path.display());
+        }
+
+        Ok(Self {
+            chain_id,
+            boot_info,
+            kv_store,
+        })
+    }
+}
+
+/// A cursor over the body of a witness archive.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(anyhow!("witness archive is truncated"));
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests of loading the preimages that native execution wrote to the data directory.
+
+use std::{env, fs, path::PathBuf};
+
+use alloy_primitives::{hex, keccak256};
+use client_utils::OracleKey;
+use host_utils::helpers::{load_kv_store, write_preimage};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+/// Returns an empty data directory unique to the test.
+fn data_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("data-dir-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn load_kv_store_reads_preimages() {
+    let dir = data_dir("valid");
+    let key: OracleKey = PreimageKey::new(*keccak256(b"value"), PreimageKeyType::Keccak256).into();
+    write_preimage(&dir, key, b"value").unwrap();
+    // Other files in the data directory are skipped.
+    fs::write(dir.join("access.log"), "").unwrap();
+
+    let kv_store = load_kv_store(&dir).unwrap();
+    assert_eq!(kv_store.len(), 1);
+    assert_eq!(
+        kv_store.get(&key).map(Vec::as_slice),
+        Some(b"value".as_slice())
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn load_kv_store_rejects_malformed_keys() {
+    // 0x00 is not a preimage key type.
+    let dir = data_dir("invalid-type");
+    fs::write(
+        dir.join(format!("{}.bin", hex::encode([0u8; 32]))),
+        b"value",
+    )
+    .unwrap();
+    assert!(load_kv_store(&dir).is_err());
+    fs::remove_dir_all(dir).unwrap();
+
+    let dir = data_dir("invalid-length");
+    fs::write(dir.join("0200.bin"), b"value").unwrap();
+    assert!(load_kv_store(&dir).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
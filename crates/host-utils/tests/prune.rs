@@ -0,0 +1,67 @@
+//! Tests of pruning the preimages that the native client did not read from the witness.
+
+use std::collections::HashSet;
+
+use alloy_primitives::{keccak256, B256};
+use client_utils::{KvStore, OracleKey};
+use host_utils::helpers::prune_kv_store;
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+fn key(hash: B256, key_type: PreimageKeyType) -> OracleKey {
+    PreimageKey::new(*hash, key_type).into()
+}
+
+/// Inserts the blob element at `index` of the blob with the given commitment, along with the
+/// keccak256 preimage of its key, and returns the hash of its key.
+fn insert_blob_element(kv_store: &mut KvStore, commitment: [u8; 48], index: u8) -> B256 {
+    let mut blob_key = [0u8; 80];
+    blob_key[..48].copy_from_slice(&commitment);
+    blob_key[79] = index;
+    let hash = keccak256(blob_key);
+    kv_store.insert(key(hash, PreimageKeyType::Keccak256), blob_key.to_vec());
+    kv_store.insert(key(hash, PreimageKeyType::Blob), vec![index; 32]);
+    hash
+}
+
+#[test]
+fn prune_keeps_read_and_verification_preimages() {
+    let mut kv_store = KvStore::default();
+
+    let local = key(B256::with_last_byte(1), PreimageKeyType::Local);
+    kv_store.insert(local, vec![0x01; 32]);
+
+    let read = key(keccak256(b"read"), PreimageKeyType::Keccak256);
+    kv_store.insert(read, b"read".to_vec());
+    let unread = key(keccak256(b"unread"), PreimageKeyType::Keccak256);
+    kv_store.insert(unread, b"unread".to_vec());
+
+    // The precompile preimage is verified against the keccak256 preimage of its key.
+    let precompile_hash = keccak256(b"precompile input");
+    let precompile = key(precompile_hash, PreimageKeyType::Precompile);
+    kv_store.insert(precompile, vec![0x01]);
+    kv_store.insert(
+        key(precompile_hash, PreimageKeyType::Keccak256),
+        b"precompile input".to_vec(),
+    );
+
+    // Only the first element of the first blob is read, but the whole blob is verified.
+    let read_element = insert_blob_element(&mut kv_store, [0x11; 48], 0);
+    let unread_element = insert_blob_element(&mut kv_store, [0x11; 48], 1);
+    let unread_blob = insert_blob_element(&mut kv_store, [0x22; 48], 0);
+
+    let accessed = HashSet::from([read, precompile, key(read_element, PreimageKeyType::Blob)]);
+    let (removed_entries, removed_bytes) = prune_kv_store(&mut kv_store, &accessed);
+
+    assert_eq!(removed_entries, 3);
+    assert_eq!(removed_bytes, (32 + 6) + (32 + 80) + (32 + 32));
+    assert!(kv_store.contains_key(&local));
+    assert!(kv_store.contains_key(&read));
+    assert!(!kv_store.contains_key(&unread));
+    assert!(kv_store.contains_key(&key(precompile_hash, PreimageKeyType::Keccak256)));
+    for hash in [read_element, unread_element] {
+        assert!(kv_store.contains_key(&key(hash, PreimageKeyType::Blob)));
+        assert!(kv_store.contains_key(&key(hash, PreimageKeyType::Keccak256)));
+    }
+    assert!(!kv_store.contains_key(&key(unread_blob, PreimageKeyType::Blob)));
+    assert!(!kv_store.contains_key(&key(unread_blob, PreimageKeyType::Keccak256)));
+}
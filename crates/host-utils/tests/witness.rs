@@ -0,0 +1,106 @@
+//! Tests of the packed witness archive.
+
+use std::{env, fs, path::PathBuf};
+
+use alloy_primitives::{keccak256, B256};
+use client_utils::{hash_rollup_config, KvStore, RawBootInfo};
+use host_utils::witness::WitnessArchive;
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use kona_primitives::RollupConfig;
+
+/// Returns the path of an archive unique to the test.
+fn archive_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("{}-{}.witness", name, std::process::id()))
+}
+
+/// Returns an archive for a chain that is not in kona's registry, so the rollup config is
+/// supplied by the host.
+fn archive() -> WitnessArchive {
+    let mut rollup_config = RollupConfig::from_l2_chain_id(10).expect("OP Mainnet is registered");
+    rollup_config.l2_chain_id = 424242;
+    let boot_info = RawBootInfo {
+        l1_head: B256::repeat_byte(0x01),
+        l2_output_root: B256::repeat_byte(0x02),
+        l2_output_root_block: 0,
+        l2_claim: B256::repeat_byte(0x03),
+        l2_claim_block: 100,
+        chain_id: rollup_config.l2_chain_id,
+        rollup_config_hash: hash_rollup_config(&rollup_config),
+        rollup_config: Some(rollup_config),
+    };
+
+    let mut kv_store = KvStore::default();
+    for value in [b"first".as_slice(), b"second".as_slice(), b"".as_slice()] {
+        let key = PreimageKey::new(*keccak256(value), PreimageKeyType::Keccak256);
+        kv_store.insert(key.into(), value.to_vec());
+    }
+
+    WitnessArchive {
+        chain_id: boot_info.chain_id,
+        boot_info,
+        kv_store,
+    }
+}
+
+#[test]
+fn archive_round_trips() {
+    let path = archive_path("round-trip");
+    let archive = archive();
+    archive.write(&path).unwrap();
+    let read = WitnessArchive::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.chain_id, archive.chain_id);
+    assert_eq!(read.boot_info.abi_encode(), archive.boot_info.abi_encode());
+    assert_eq!(
+        read.boot_info
+            .rollup_config
+            .as_ref()
+            .map(hash_rollup_config),
+        Some(archive.boot_info.rollup_config_hash)
+    );
+    assert_eq!(read.kv_store, archive.kv_store);
+}
+
+#[test]
+fn archive_without_rollup_config_round_trips() {
+    let path = archive_path("registry");
+    let mut archive = archive();
+    let rollup_config = RollupConfig::from_l2_chain_id(10).unwrap();
+    archive.chain_id = 10;
+    archive.boot_info.chain_id = 10;
+    archive.boot_info.rollup_config_hash = hash_rollup_config(&rollup_config);
+    archive.boot_info.rollup_config = None;
+    archive.write(&path).unwrap();
+    let read = WitnessArchive::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(read.boot_info.rollup_config.is_none());
+    assert_eq!(read.kv_store, archive.kv_store);
+}
+
+#[test]
+fn corrupt_archive_is_rejected() {
+    let path = archive_path("corrupt");
+    archive().write(&path).unwrap();
+    let bytes = fs::read(&path).unwrap();
+
+    // A flipped byte in a record no longer matches the checksum.
+    let mut corrupt_body = bytes.clone();
+    let last_value = corrupt_body.len() - 33;
+    corrupt_body[last_value] ^= 0xFF;
+    fs::write(&path, &corrupt_body).unwrap();
+    assert!(WitnessArchive::read(&path).is_err());
+
+    // Neither does a flipped byte in the checksum itself.
+    let mut corrupt_checksum = bytes.clone();
+    *corrupt_checksum.last_mut().unwrap() ^= 0xFF;
+    fs::write(&path, &corrupt_checksum).unwrap();
+    assert!(WitnessArchive::read(&path).is_err());
+
+    // Nor does a truncated archive.
+    fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    assert!(WitnessArchive::read(&path).is_err());
+
+    fs::remove_file(&path).unwrap();
+}
@@ -16,10 +16,11 @@ use alloy_consensus::Sealed;
 use cfg_if::cfg_if;
 
 use client_utils::{
-    driver::MultiBlockDerivationDriver, l2_chain_provider::MultiblockOracleL2ChainProvider,
+    driver::{deposit_only_fallback, MultiBlockDerivationDriver},
+    l2_chain_provider::MultiblockOracleL2ChainProvider,
 };
 
-use log::info;
+use log::{info, warn};
 
 extern crate alloc;
 
@@ -31,10 +32,13 @@ cfg_if! {
 
         use client_utils::{
             RawBootInfo,
-            InMemoryOracle
+            ArchivedInMemoryOracle,
+            WitnessEncoding,
+            STDIN_VERSION
         };
         use alloc::vec::Vec;
     } else {
+        use client_utils::{load_boot_info, trace::DerivationTrace, AccessTrackingOracle};
         use kona_client::CachingOracle;
     }
 }
@@ -52,27 +56,41 @@ fn main() {
                 use client_utils::precompiles::ZKVMPrecompileOverride;
 
                 println!("cycle-tracker-start: boot-load");
-                let boot = sp1_zkvm::io::read::<RawBootInfo>();
-                sp1_zkvm::io::commit_slice(&boot.abi_encode());
-                let boot: Arc<BootInfo> = Arc::new(boot.into());
+                let stdin_version = sp1_zkvm::io::read::<u32>();
+                assert_eq!(stdin_version, STDIN_VERSION, "unsupported stdin version");
+                let mut raw_boot_info = sp1_zkvm::io::read::<RawBootInfo>();
+                let boot: Arc<BootInfo> = Arc::new(raw_boot_info.clone().into());
                 println!("cycle-tracker-end: boot-load");
 
                 println!("cycle-tracker-start: oracle-load");
+                let encoding = sp1_zkvm::io::read::<WitnessEncoding>();
                 let kv_store_bytes: Vec<u8> = sp1_zkvm::io::read_vec();
-                let oracle = Arc::new(InMemoryOracle::from_raw_bytes(kv_store_bytes));
+                let oracle = ArchivedInMemoryOracle::from_raw_bytes(kv_store_bytes, encoding)
+                    .expect("invalid key value store");
                 println!("cycle-tracker-end: oracle-load");
 
-                println!("cycle-tracker-start: oracle-verify");
-                oracle.verify().expect("key value verification failed");
-                println!("cycle-tracker-end: oracle-verify");
+                cfg_if! {
+                    // With lazy verification, each preimage is verified the first time it is
+                    // read, so unused witness entries are never hashed.
+                    if #[cfg(feature = "lazy-oracle")] {
+                        let oracle = Arc::new(oracle.with_lazy_verification(raw_boot_info.clone()));
+                    } else {
+                        println!("cycle-tracker-start: oracle-verify");
+                        oracle.verify(&raw_boot_info).expect("key value verification failed");
+                        println!("cycle-tracker-end: oracle-verify");
+                        let oracle = Arc::new(oracle);
+                    }
+                }
 
                 let precompile_overrides = ZKVMPrecompileOverride::default();
 
             // If we are compiling for online mode, create a caching oracle that speaks to the
             // fetcher via hints, and gather boot info from this oracle.
             } else {
-                let oracle = Arc::new(CachingOracle::new(1024));
-                let boot = Arc::new(BootInfo::load(oracle.as_ref()).await.unwrap());
+                let oracle = Arc::new(AccessTrackingOracle::new(CachingOracle::new(1024)));
+                // The boot info is loaded with the rollup config supplied by the host, if any.
+                let boot: Arc<BootInfo> =
+                    Arc::new(load_boot_info(oracle.as_ref()).await.unwrap());
 
                 let precompile_overrides = NoPrecompileOverride;
             }
@@ -89,7 +107,7 @@ fn main() {
         println!("cycle-tracker-start: derivation-instantiation");
         let mut driver = MultiBlockDerivationDriver::new(
             boot.as_ref(),
-            oracle.as_ref(),
+            oracle.clone(),
             beacon,
             l1_provider,
             l2_provider.clone(),
@@ -98,8 +116,17 @@ fn main() {
         .unwrap();
         println!("cycle-tracker-end: derivation-instantiation");
 
+        cfg_if! {
+            // Commit to the boot info once the number of the starting L2 block has been derived
+            // from the L2 output root, so the public values show the range of blocks proven.
+            if #[cfg(target_os = "zkvm")] {
+                raw_boot_info.l2_output_root_block = driver.l2_output_root_block;
+                sp1_zkvm::io::commit_slice(&raw_boot_info.abi_encode());
+            }
+        }
+
         let mut l2_block_info = driver.l2_safe_head;
-        let mut new_block_header = &driver.l2_safe_head_header.inner().clone();
+        let mut new_block_header = driver.l2_safe_head_header.inner().clone();
 
         println!("cycle-tracker-start: execution-instantiation");
         let mut executor = StatelessL2BlockExecutor::builder(&boot.rollup_config)
@@ -111,8 +138,22 @@ fn main() {
             .unwrap();
         println!("cycle-tracker-end: execution-instantiation");
 
+        cfg_if! {
+            // Natively, record how each block was derived, so failed proofs can be debugged.
+            if #[cfg(not(target_os = "zkvm"))] {
+                let mut trace =
+                    DerivationTrace::from_env().expect("failed to create derivation trace");
+            }
+        }
+
         'step: loop {
-            let l2_attrs_with_parents = driver.produce_payloads().await.unwrap();
+            println!("cycle-tracker-report-start: derivation");
+            let derived = driver.produce_payloads().await;
+            println!("cycle-tracker-report-end: derivation");
+            let l2_attrs_with_parents = match derived {
+                Ok(payloads) => payloads,
+                Err(e) => panic!("Derivation failed: {}", e),
+            };
             if l2_attrs_with_parents.is_empty() {
                 continue;
             }
@@ -123,10 +164,41 @@ fn main() {
                     "Executing Payload for L2 Block: {}",
                     payload.parent.block_info.number + 1
                 );
+                cfg_if! {
+                    if #[cfg(not(target_os = "zkvm"))] {
+                        let payload_transactions = payload.attributes.transactions.len();
+                    }
+                }
                 println!("cycle-tracker-report-start: block-execution");
-                new_block_header = executor
-                    .execute_payload(payload.attributes.clone())
-                    .unwrap();
+                let failed_fetches = l2_provider.failed_fetches();
+                let attributes = match executor.execute_payload(payload.attributes.clone()) {
+                    Ok(header) => {
+                        new_block_header = header.clone();
+                        payload.attributes
+                    }
+                    // An invalid payload is replaced with a block containing only its deposits,
+                    // which must always execute. Missing witness data aborts the run instead.
+                    Err(e) => {
+                        warn!(
+                            "Payload for L2 Block {} failed to execute: {:?}",
+                            payload.parent.block_info.number + 1,
+                            e
+                        );
+                        let deposit_only = deposit_only_fallback(
+                            &l2_provider,
+                            failed_fetches,
+                            &payload.attributes,
+                            e,
+                        )
+                        .expect("Payload failed to execute");
+                        warn!("Replacing the payload with a deposit-only block.");
+                        new_block_header = executor
+                            .execute_payload(deposit_only.clone())
+                            .expect("Deposit-only payload failed to execute")
+                            .clone();
+                        deposit_only
+                    }
+                };
                 println!("cycle-tracker-report-end: block-execution");
                 let new_block_number = new_block_header.number;
                 assert_eq!(new_block_number, payload.parent.block_info.number + 1);
@@ -134,8 +206,7 @@ fn main() {
                 // Generate the Payload Envelope, which can be used to derive cached data.
                 let l2_payload_envelope: L2ExecutionPayloadEnvelope = OpBlock {
                     header: new_block_header.clone(),
-                    body: payload
-                        .attributes
+                    body: attributes
                         .transactions
                         .iter()
                         .map(|raw_tx| OpTxEnvelope::decode_2718(&mut raw_tx.as_ref()).unwrap())
@@ -150,9 +221,21 @@ fn main() {
 
                 // Add all data from this block's execution to the cache.
                 l2_block_info = l2_provider
-                    .update_cache(new_block_header, l2_payload_envelope, &boot.rollup_config)
+                    .update_cache(&new_block_header, l2_payload_envelope, &boot.rollup_config)
                     .unwrap();
 
+                cfg_if! {
+                    if #[cfg(not(target_os = "zkvm"))] {
+                        trace.record_block(
+                            &driver,
+                            &l2_block_info,
+                            &attributes,
+                            attributes.transactions.len() != payload_transactions,
+                        )
+                        .expect("failed to write derivation trace");
+                    }
+                }
+
                 // Increment last_block_num and check if we have reached the claim block.
                 if new_block_number == boot.l2_claim_block {
                     break 'step;
@@ -172,6 +255,17 @@ fn main() {
 
         println!("Completed Proof. Output Root: {}", output_root);
 
+        cfg_if! {
+            // Report how much of the witness was actually read when verifying lazily.
+            if #[cfg(all(target_os = "zkvm", feature = "lazy-oracle"))] {
+                oracle.report_lazy_verification_counts();
+            // Record which preimages were read natively, so the host can prune the rest from the
+            // witness.
+            } else if #[cfg(not(target_os = "zkvm"))] {
+                oracle.write_access_log().expect("failed to write access log");
+            }
+        }
+
         ////////////////////////////////////////////////////////////////
         //                          EPILOGUE                          //
         ////////////////////////////////////////////////////////////////
@@ -21,9 +21,10 @@ cfg_if! {
     // from SP1 and compile to a program that can be run in zkVM.
     if #[cfg(target_os = "zkvm")] {
         sp1_zkvm::entrypoint!(main);
-        use client_utils::{RawBootInfo, InMemoryOracle};
+        use client_utils::{RawBootInfo, ArchivedInMemoryOracle, WitnessEncoding, STDIN_VERSION};
         use alloc::vec::Vec;
     } else {
+        use client_utils::{load_boot_info, AccessTrackingOracle};
         use kona_client::CachingOracle;
     }
 }
@@ -41,27 +42,41 @@ fn main() {
                 use client_utils::precompiles::ZKVMPrecompileOverride;
 
                 println!("cycle-tracker-start: boot-load");
-                let raw_boot_info = sp1_zkvm::io::read::<RawBootInfo>();
-                sp1_zkvm::io::commit_slice(&raw_boot_info.abi_encode());
-                let boot: Arc<BootInfo> = Arc::new(raw_boot_info.into());
+                let stdin_version = sp1_zkvm::io::read::<u32>();
+                assert_eq!(stdin_version, STDIN_VERSION, "unsupported stdin version");
+                let mut raw_boot_info = sp1_zkvm::io::read::<RawBootInfo>();
+                let boot: Arc<BootInfo> = Arc::new(raw_boot_info.clone().into());
                 println!("cycle-tracker-end: boot-load");
 
                 println!("cycle-tracker-start: oracle-load");
+                let encoding = sp1_zkvm::io::read::<WitnessEncoding>();
                 let kv_store_bytes: Vec<u8> = sp1_zkvm::io::read_vec();
-                let oracle = Arc::new(InMemoryOracle::from_raw_bytes(kv_store_bytes));
+                let oracle = ArchivedInMemoryOracle::from_raw_bytes(kv_store_bytes, encoding)
+                    .expect("invalid key value store");
                 println!("cycle-tracker-end: oracle-load");
 
-                println!("cycle-tracker-start: oracle-verify");
-                oracle.verify().expect("key value verification failed");
-                println!("cycle-tracker-end: oracle-verify");
+                cfg_if! {
+                    // With lazy verification, each preimage is verified the first time it is
+                    // read, so unused witness entries are never hashed.
+                    if #[cfg(feature = "lazy-oracle")] {
+                        let oracle = Arc::new(oracle.with_lazy_verification(raw_boot_info.clone()));
+                    } else {
+                        println!("cycle-tracker-start: oracle-verify");
+                        oracle.verify(&raw_boot_info).expect("key value verification failed");
+                        println!("cycle-tracker-end: oracle-verify");
+                        let oracle = Arc::new(oracle);
+                    }
+                }
 
                 let precompile_overrides = ZKVMPrecompileOverride::default();
 
             // If we are compiling for online mode, create a caching oracle that speaks to the
             // fetcher via hints, and gather boot info from this oracle.
             } else {
-                let oracle = Arc::new(CachingOracle::new(1024));
-                let boot = Arc::new(BootInfo::load(oracle.as_ref()).await.unwrap());
+                let oracle = Arc::new(AccessTrackingOracle::new(CachingOracle::new(1024)));
+                // The boot info is loaded with the rollup config supplied by the host, if any.
+                let boot: Arc<BootInfo> =
+                    Arc::new(load_boot_info(oracle.as_ref()).await.unwrap());
                 let precompile_overrides = NoPrecompileOverride;
             }
         }
@@ -87,10 +102,19 @@ fn main() {
         println!("cycle-tracker-end: derivation-instantiation");
 
         println!("cycle-tracker-start: payload-derivation");
-        let L2AttributesWithParent { attributes, .. } =
-            driver.produce_disputed_payload().await.unwrap();
+        let payload = driver.produce_disputed_payload().await.unwrap();
         println!("cycle-tracker-end: payload-derivation");
 
+        cfg_if! {
+            // Commit to the boot info once the number of the starting L2 block is known from the
+            // parent of the disputed payload, so the public values show the block proven.
+            if #[cfg(target_os = "zkvm")] {
+                raw_boot_info.l2_output_root_block = payload.parent.block_info.number;
+                sp1_zkvm::io::commit_slice(&raw_boot_info.abi_encode());
+            }
+        }
+        let L2AttributesWithParent { attributes, .. } = payload;
+
         println!("cycle-tracker-start: execution-instantiation");
         let mut executor = StatelessL2BlockExecutor::builder(&boot.rollup_config)
             .with_parent_header(driver.take_l2_safe_head_header())
@@ -109,6 +133,17 @@ fn main() {
         let output_root = executor.compute_output_root().unwrap();
         println!("cycle-tracker-end: output-root");
 
+        cfg_if! {
+            // Report how much of the witness was actually read when verifying lazily.
+            if #[cfg(all(target_os = "zkvm", feature = "lazy-oracle"))] {
+                oracle.report_lazy_verification_counts();
+            // Record which preimages were read natively, so the host can prune the rest from the
+            // witness.
+            } else if #[cfg(not(target_os = "zkvm"))] {
+                oracle.write_access_log().expect("failed to write access log");
+            }
+        }
+
         // ////////////////////////////////////////////////////////////////
         // //                          EPILOGUE                          //
         // ////////////////////////////////////////////////////////////////
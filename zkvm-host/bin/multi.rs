@@ -2,14 +2,19 @@ use std::fs;
 
 use anyhow::Result;
 use clap::Parser;
-use client_utils::precompiles::PRECOMPILE_HOOK_FD;
+use client_utils::{precompiles::PRECOMPILE_HOOK_FD, WitnessEncoding, LAZY_VERIFICATION_HOOK_FD};
 use host_utils::{
+    altda::fetch_altda_inputs,
     fetcher::{ChainMode, SP1KonaDataFetcher},
-    get_proof_stdin, ProgramType,
+    get_proof_stdin,
+    native::run_native_client,
+    write_witness_archive, ProgramType,
 };
-use kona_host::start_server_and_native_client;
 use sp1_sdk::{utils, ExecutionReport, ProverClient};
-use zkvm_host::{precompile_hook, BnStats, ExecutionStats};
+use zkvm_host::{
+    lazy_verification_hook, precompile_hook, take_lazy_verification_counts, BnStats,
+    ExecutionStats, PrecompileStats,
+};
 
 pub const MULTI_BLOCK_ELF: &[u8] = include_bytes!("../../elf/validity-client-elf");
 
@@ -35,6 +40,15 @@ struct Args {
     /// Generate proof.
     #[arg(short, long)]
     prove: bool,
+
+    /// Compress the witness with LZ4, trading decompression cycles for a smaller stdin.
+    #[arg(long)]
+    compress: bool,
+
+    /// Write a trace of how each block was derived to the data directory, which the `trace`
+    /// binary prints and compares against op-node.
+    #[arg(long)]
+    trace: bool,
 }
 
 /// Based on the stats flag, print out simple or detailed statistics.
@@ -62,10 +76,38 @@ async fn print_stats(data_fetcher: &SP1KonaDataFetcher, args: &Args, report: &Ex
             nb_blocks,
             nb_transactions,
             total_gas_used,
+            decompression_cycles: *report.cycle_tracker.get("oracle-decompress").unwrap_or(&0),
+            derivation_cycles: *report.cycle_tracker.get("derivation").unwrap_or(&0),
+            l2_cache_update_cycles: *report.cycle_tracker.get("l2-cache-update").unwrap_or(&0),
+            l2_header_walk_back_cycles: *report
+                .cycle_tracker
+                .get("l2-header-walk-back")
+                .unwrap_or(&0),
+            lazy_verification_counts: take_lazy_verification_counts(),
             bn_stats: BnStats {
                 bn_add_cycles: *report.cycle_tracker.get("precompile-bn-add").unwrap_or(&0),
                 bn_mul_cycles: *report.cycle_tracker.get("precompile-bn-mul").unwrap_or(&0),
                 bn_pair_cycles: *report.cycle_tracker.get("precompile-bn-pair").unwrap_or(&0),
+            },
+            precompile_stats: PrecompileStats {
+                ecrecover_cycles: *report
+                    .cycle_tracker
+                    .get("precompile-ecrecover")
+                    .unwrap_or(&0),
+                sha256_cycles: *report.cycle_tracker.get("precompile-sha256").unwrap_or(&0),
+                ripemd160_cycles: *report
+                    .cycle_tracker
+                    .get("precompile-ripemd160")
+                    .unwrap_or(&0),
+                modexp_cycles: *report.cycle_tracker.get("precompile-modexp").unwrap_or(&0),
+                kzg_point_evaluation_cycles: *report
+                    .cycle_tracker
+                    .get("precompile-kzg-point-evaluation")
+                    .unwrap_or(&0),
+                p256_verify_cycles: *report
+                    .cycle_tracker
+                    .get("precompile-p256-verify")
+                    .unwrap_or(&0),
             }
         }
     );
@@ -94,14 +136,24 @@ async fn main() -> Result<()> {
         // Overwrite existing data directory.
         fs::create_dir_all(&data_dir).unwrap();
 
-        // Start the server and native client.
-        start_server_and_native_client(host_cli.clone())
-            .await
-            .unwrap();
+        // Fetch the inputs of alt-DA commitments, which kona-host cannot fetch itself.
+        fetch_altda_inputs(&data_fetcher, &host_cli).await?;
+
+        // Start the server and native client, which records which preimages it reads so unread
+        // ones can be pruned, and how it derived each block if tracing.
+        run_native_client(&host_cli, args.trace).await.unwrap();
+
+        // Pack the witness into a single file, which is faster to load and easy to copy.
+        write_witness_archive(&host_cli)?;
     }
 
     // Get the stdin for the block.
-    let sp1_stdin = get_proof_stdin(&host_cli)?;
+    let encoding = if args.compress {
+        WitnessEncoding::Lz4
+    } else {
+        WitnessEncoding::Raw
+    };
+    let sp1_stdin = get_proof_stdin(&host_cli, encoding)?;
 
     let prover = ProverClient::new();
 
@@ -129,6 +181,7 @@ async fn main() -> Result<()> {
         let (_, report) = prover
             .execute(MULTI_BLOCK_ELF, sp1_stdin.clone())
             .with_hook(PRECOMPILE_HOOK_FD, precompile_hook)
+            .with_hook(LAZY_VERIFICATION_HOOK_FD, lazy_verification_hook)
             .run()
             .unwrap();
 
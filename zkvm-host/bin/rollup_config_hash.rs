@@ -0,0 +1,43 @@
+//! Prints the hash of the rollup config that proofs for a chain commit to, which is the
+//! `rollupConfigHash` to deploy `ZKL2OutputOracle` with.
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use client_utils::{hash_rollup_config, load_rollup_config, ROLLUP_CONFIG_PATH_ENV};
+use dotenv::dotenv;
+use kona_primitives::RollupConfig;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Chain ID, whose rollup config is looked up in kona's registry unless one is supplied at
+    /// `ROLLUP_CONFIG_PATH`.
+    #[arg(short, long)]
+    chain_id: u64,
+}
+
+fn main() -> Result<()> {
+    dotenv().ok();
+    let args = Args::parse();
+
+    let rollup_config = match load_rollup_config()? {
+        Some(rollup_config) => rollup_config,
+        None => RollupConfig::from_l2_chain_id(args.chain_id).ok_or_else(|| {
+            anyhow!(
+                "no rollup config registered for chain id {}, set {}",
+                args.chain_id,
+                ROLLUP_CONFIG_PATH_ENV
+            )
+        })?,
+    };
+    if rollup_config.l2_chain_id != args.chain_id {
+        return Err(anyhow!(
+            "rollup config is for chain id {}, not {}",
+            rollup_config.l2_chain_id,
+            args.chain_id
+        ));
+    }
+
+    println!("{}", hash_rollup_config(&rollup_config));
+    Ok(())
+}
@@ -6,9 +6,11 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use client_utils::RawBootInfo;
-use host_utils::{fetcher::SP1KonaDataFetcher, get_agg_proof_stdin, get_proof_stdin, ProgramType};
-use kona_host::start_server_and_native_client;
+use client_utils::{RawBootInfo, WitnessEncoding};
+use host_utils::{
+    altda::fetch_altda_inputs, fetcher::SP1KonaDataFetcher, get_agg_proof_stdin, get_proof_stdin,
+    native::run_native_client, write_witness_archive, ProgramType,
+};
 use log::info;
 use serde::{Deserialize, Serialize};
 use sp1_sdk::{
@@ -26,6 +28,9 @@ pub const AGG_ELF: &[u8] = include_bytes!("../../elf/aggregation-client-elf");
 struct SpanProofRequest {
     start: u64,
     end: u64,
+    /// Compress the witness with LZ4, which makes the proof request smaller to upload.
+    #[serde(default)]
+    compress: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -80,10 +85,22 @@ async fn request_span_proof(
     // Overwrite existing data directory.
     fs::create_dir_all(&data_dir)?;
 
-    // Start the server and native client.
-    start_server_and_native_client(host_cli.clone()).await?;
+    // Fetch the inputs of alt-DA commitments, which kona-host cannot fetch itself.
+    fetch_altda_inputs(&data_fetcher, &host_cli).await?;
+
+    // Start the server and native client, which records which preimages it reads so unread ones
+    // can be pruned.
+    run_native_client(&host_cli, false).await?;
+
+    // Pack the witness into a single file, which is faster to load and easy to copy.
+    write_witness_archive(&host_cli)?;
 
-    let sp1_stdin = get_proof_stdin(&host_cli)?;
+    let encoding = if payload.compress {
+        WitnessEncoding::Lz4
+    } else {
+        WitnessEncoding::Raw
+    };
+    let sp1_stdin = get_proof_stdin(&host_cli, encoding)?;
 
     let prover = NetworkProver::new();
     let proof_id = prover
@@ -103,10 +120,11 @@ async fn request_agg_proof(
         .map(|sp| bincode::deserialize(sp).unwrap())
         .collect();
 
+    // The public values are the ABI-encoded BootInfo.
     let boot_infos: Vec<RawBootInfo> = proofs_with_pv
-        .iter_mut()
-        .map(|proof| proof.public_values.read::<RawBootInfo>())
-        .collect();
+        .iter()
+        .map(|proof| RawBootInfo::abi_decode(proof.public_values.as_slice()))
+        .collect::<Result<_, _>>()?;
 
     let proofs: Vec<SP1Proof> = proofs_with_pv
         .iter_mut()
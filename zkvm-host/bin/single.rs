@@ -2,12 +2,14 @@ use std::{env, fs};
 
 use anyhow::Result;
 use clap::Parser;
-use host_utils::{fetcher::SP1KonaDataFetcher, get_proof_stdin, ProgramType};
-use kona_host::start_server_and_native_client;
+use host_utils::{
+    altda::fetch_altda_inputs, fetcher::SP1KonaDataFetcher, get_proof_stdin,
+    native::run_native_client, write_witness_archive, ProgramType,
+};
 use num_format::{Locale, ToFormattedString};
 use sp1_sdk::{utils, ProverClient};
 
-use client_utils::precompiles::PRECOMPILE_HOOK_FD;
+use client_utils::{precompiles::PRECOMPILE_HOOK_FD, WitnessEncoding};
 use zkvm_host::precompile_hook;
 
 pub const SINGLE_BLOCK_ELF: &[u8] = include_bytes!("../../elf/zkvm-client-elf");
@@ -54,14 +56,19 @@ async fn main() -> Result<()> {
         // Overwrite existing data directory.
         fs::create_dir_all(&data_dir).unwrap();
 
-        // Start the server and native client.
-        start_server_and_native_client(host_cli.clone())
-            .await
-            .unwrap();
+        // Fetch the inputs of alt-DA commitments, which kona-host cannot fetch itself.
+        fetch_altda_inputs(&data_fetcher, &host_cli).await?;
+
+        // Start the server and native client, which records which preimages it reads so unread
+        // ones can be pruned.
+        run_native_client(&host_cli, false).await.unwrap();
+
+        // Pack the witness into a single file, which is faster to load and easy to copy.
+        write_witness_archive(&host_cli)?;
     }
 
     // Get the stdin for the block.
-    let sp1_stdin = get_proof_stdin(&host_cli)?;
+    let sp1_stdin = get_proof_stdin(&host_cli, WitnessEncoding::Raw)?;
 
     let prover = ProverClient::new();
     let (_, report) = prover
@@ -0,0 +1,196 @@
+//! Prints the derivation trace that the native client wrote for a range with `multi --trace`, and
+//! optionally compares it against what op-node derived for the same blocks.
+
+use std::{env, str::FromStr};
+
+use alloy::{
+    providers::{Provider, ProviderBuilder},
+    transports::http::reqwest::Url,
+};
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use client_utils::trace::DerivationTrace;
+use host_utils::{fetcher::SP1KonaDataFetcher, helpers::derivation_trace_path, ProgramType};
+use serde::Deserialize;
+use sp1_sdk::utils;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Start L2 block number.
+    #[arg(short, long)]
+    start: u64,
+
+    /// End L2 block number.
+    #[arg(short, long)]
+    end: u64,
+
+    /// Compare the trace against the rollup node at `L2_NODE_RPC`.
+    #[arg(short, long)]
+    compare: bool,
+}
+
+/// A block id, as returned by op-node.
+#[derive(Deserialize)]
+struct BlockId {
+    hash: B256,
+    number: u64,
+}
+
+/// An L2 block reference, as returned by op-node.
+#[derive(Deserialize)]
+struct L2BlockRef {
+    hash: B256,
+    number: u64,
+    l1origin: BlockId,
+}
+
+/// The response of `optimism_outputAtBlock`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputAtBlock {
+    block_ref: L2BlockRef,
+}
+
+/// The response of `optimism_syncStatus`.
+#[derive(Deserialize)]
+struct SyncStatus {
+    safe_l2: L2BlockRef,
+    finalized_l2: L2BlockRef,
+}
+
+/// Prints one row per derived block.
+fn print_trace(trace: &DerivationTrace) {
+    println!(
+        "{:>10} {:>10} {:>15} {:>5} {:>8} {:>6} {:>15} {:>12}",
+        "L2 Block",
+        "L1 Origin",
+        "Pipeline Origin",
+        "Txs",
+        "Deposits",
+        "Steps",
+        "Origin Advances",
+        "Deposit Only"
+    );
+    for block in &trace.blocks {
+        println!(
+            "{:>10} {:>10} {:>15} {:>5} {:>8} {:>6} {:>15} {:>12}",
+            block.number,
+            block.l1_origin_number,
+            block
+                .pipeline_origin
+                .map_or_else(|| "-".to_string(), |origin| origin.to_string()),
+            block.transactions,
+            block.deposits,
+            block.steps,
+            block.origin_advances,
+            block.deposit_only
+        );
+    }
+}
+
+/// Compares the hash and L1 origin of every block in the trace against op-node, and returns the
+/// number of blocks that differ.
+async fn compare_trace(trace: &DerivationTrace, rollup_node_rpc: &str) -> Result<usize> {
+    let provider = ProviderBuilder::default().on_http(Url::from_str(rollup_node_rpc)?);
+
+    let status: SyncStatus = provider
+        .raw_request("optimism_syncStatus".into(), ())
+        .await?;
+    println!(
+        "op-node safe head: {} ({}), finalized head: {}",
+        status.safe_l2.number, status.safe_l2.hash, status.finalized_l2.number
+    );
+
+    let mut mismatches = 0;
+    for block in &trace.blocks {
+        if block.number > status.safe_l2.number {
+            println!(
+                "Block {}: past the op-node safe head, so op-node may still replace it.",
+                block.number
+            );
+        }
+
+        let output: OutputAtBlock = provider
+            .raw_request(
+                "optimism_outputAtBlock".into(),
+                (format!("{:#x}", block.number),),
+            )
+            .await?;
+        let expected = output.block_ref;
+
+        let mut differs = false;
+        if expected.hash != block.hash {
+            println!(
+                "Block {}: hash {} differs from op-node {}",
+                block.number, block.hash, expected.hash
+            );
+            differs = true;
+        }
+        if expected.l1origin.number != block.l1_origin_number
+            || expected.l1origin.hash != block.l1_origin_hash
+        {
+            println!(
+                "Block {}: L1 origin {} ({}) differs from op-node {} ({})",
+                block.number,
+                block.l1_origin_number,
+                block.l1_origin_hash,
+                expected.l1origin.number,
+                expected.l1origin.hash
+            );
+            differs = true;
+        }
+        if differs {
+            mismatches += 1;
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Print and compare the derivation trace of a range.
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    utils::setup_logger();
+    let args = Args::parse();
+
+    let data_fetcher = SP1KonaDataFetcher::new();
+    let host_cli = data_fetcher
+        .get_host_cli_args(args.start, args.end, ProgramType::Multi)
+        .await?;
+    let data_dir = host_cli.data_dir.expect("Data directory is not set.");
+
+    let path = derivation_trace_path(&data_dir);
+    if !path.exists() {
+        return Err(anyhow!(
+            "no derivation trace at {}, run `multi --trace` for the range first",
+            path.display()
+        ));
+    }
+    let trace = DerivationTrace::read(&path)?;
+    print_trace(&trace);
+
+    // Blocks missing from the end of the trace were never derived.
+    let last_derived = trace.blocks.last().map_or(args.start, |block| block.number);
+    if last_derived < args.end {
+        println!(
+            "Derivation stopped after block {}, {} blocks short of {}.",
+            last_derived,
+            args.end - last_derived,
+            args.end
+        );
+    }
+
+    if args.compare {
+        let rollup_node_rpc = env::var("L2_NODE_RPC").expect("L2_NODE_RPC is not set.");
+        let mismatches = compare_trace(&trace, &rollup_node_rpc).await?;
+        if mismatches > 0 {
+            return Err(anyhow!("{} blocks differ from op-node", mismatches));
+        }
+        println!("All {} blocks match op-node.", trace.blocks.len());
+    }
+
+    Ok(())
+}
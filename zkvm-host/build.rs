@@ -1,6 +1,7 @@
-use std::process::Command;
+use std::{fs, process::Command};
 
 use sp1_helper::{build_program_with_args, BuildArgs};
+use sp1_sdk::{HashableKey, ProverClient};
 
 /// Build a native program.
 fn build_native_program(program: &str) {
@@ -38,6 +39,19 @@ fn build_zkvm_program(program: &str) {
     );
 }
 
+/// Write the vkey digest of a program built for the zkVM next to its ELF, so programs that verify
+/// its proofs can include it instead of hardcoding a digest that goes stale.
+fn write_vkey_digest(program: &str) {
+    let elf = fs::read(format!("../elf/{}-elf", program))
+        .unwrap_or_else(|e| panic!("Failed to read the {} ELF: {}", program, e));
+    let (_, vk) = ProverClient::new().setup(&elf);
+    fs::write(
+        format!("../elf/{}-vkey-digest", program),
+        format!("{:?}", vk.vk.hash_u32()),
+    )
+    .unwrap_or_else(|e| panic!("Failed to write the {} vkey digest: {}", program, e));
+}
+
 fn main() {
     // Don't build the single block program as it's unused.
     // let programs = vec!["zkvm-client", "validity-client"];
@@ -48,5 +62,11 @@ fn main() {
         build_zkvm_program(program);
     }
 
+    // The native clients are run under the native host.
+    build_native_program("native-host");
+
+    // The aggregation program verifies multi-block proofs against this digest, so it must be
+    // written before the aggregation program is built.
+    write_vkey_digest("validity-client");
     build_zkvm_program("aggregation-client");
 }
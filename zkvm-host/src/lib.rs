@@ -1,49 +1,71 @@
-use revm::{
-    precompile::Precompiles,
-    primitives::{Address, Bytes, Precompile},
+use std::sync::Mutex;
+
+use client_utils::{
+    decode_lazy_verification_counts,
+    precompiles::{encode_hook_output, zkvm_precompile, PrecompileHookInput},
 };
+use revm::primitives::Precompile;
 
 mod stats;
-pub use stats::{BnStats, ExecutionStats};
+pub use stats::{BnStats, ExecutionStats, PrecompileStats};
 
 pub mod utils;
 
+// The failure kinds of the hook's output, which are defined with its encoding in `client_utils`.
+pub use client_utils::precompiles::{
+    HOOK_ERROR_FATAL, HOOK_ERROR_INVALID_INPUT, HOOK_ERROR_PRECOMPILE,
+    HOOK_ERROR_UNKNOWN_PRECOMPILE,
+};
+
 /// This precompile hook substitutes the precompile with a custom one that can stub out the logic
-/// for specific operations that we don't have precompiles for. Used in `create_hook_precompile`.
+/// for specific operations that we don't have precompiles for. The client programs run every
+/// precompile in the zkVM and never write to the hook, as its results are not verified.
+///
+/// The input is a [PrecompileHookInput]. The output is `0`, the little-endian gas used and the
+/// precompile output on success, or `1` followed by one of the `HOOK_ERROR_*` kinds on failure.
 pub fn precompile_hook(_env: sp1_sdk::HookEnv, buf: &[u8]) -> Vec<Vec<u8>> {
-    let addr: Address = buf[0..20].try_into().unwrap();
-    let gas_limit = u64::from_le_bytes(buf[20..28].try_into().unwrap());
-    let input: Bytes = buf[28..].to_vec().into();
-    println!("[HOOK] Precompile addr {} called.", addr);
-
-    // Note: Fetch the latest precompiles because the gas costs are different from older versions.
-    // Otherwise, the hooked precompiles will fail.
-    let precompiles = Precompiles::latest();
-
-    let precompile = precompiles.inner().get(&addr).unwrap();
-    let result = match precompile {
-        Precompile::Standard(precompile) => precompile(&input, gas_limit),
-        _ => panic!("Annotated precompile must be a standard precompile."),
+    let Some(PrecompileHookInput {
+        address,
+        spec_id,
+        gas_limit,
+        input,
+    }) = PrecompileHookInput::decode(buf)
+    else {
+        println!("[HOOK] Invalid precompile hook input.");
+        return vec![vec![1, HOOK_ERROR_INVALID_INPUT]];
     };
+    println!("[HOOK] Precompile addr {} called.", address);
 
-    let mut serialized_vec = vec![];
-    match result {
-        Ok(result) => {
-            serialized_vec.push(0);
-            serialized_vec.extend_from_slice(&result.gas_used.to_le_bytes());
-            serialized_vec.extend_from_slice(&result.bytes);
-        }
-        Err(err) => {
-            serialized_vec.push(1);
-            match err {
-                revm::precompile::PrecompileErrors::Error(_) => {
-                    serialized_vec.push(0);
-                }
-                revm::precompile::PrecompileErrors::Fatal { .. } => {
-                    serialized_vec.push(1);
-                }
-            }
-        }
+    // Use the precompile set the guest builds for the spec that is active in it, as the gas costs
+    // and the available precompiles differ between hardforks, and OP Stack hardforks add
+    // precompiles that revm's spec sets do not have.
+    let Some(Precompile::Standard(precompile)) = zkvm_precompile(spec_id, &address) else {
+        println!(
+            "[HOOK] No standard precompile at {} in {:?}.",
+            address, spec_id
+        );
+        return vec![vec![1, HOOK_ERROR_UNKNOWN_PRECOMPILE]];
+    };
+    vec![encode_hook_output(&precompile(&input, gas_limit))]
+}
+
+/// The counts of preimages the guest verified lazily and left unused, as last reported to
+/// [lazy_verification_hook].
+static LAZY_VERIFICATION_COUNTS: Mutex<Option<(u64, u64)>> = Mutex::new(None);
+
+/// Records the counts the guest reports at `client_utils::LAZY_VERIFICATION_HOOK_FD` when it
+/// verifies the witness lazily, which [take_lazy_verification_counts] returns after execution.
+pub fn lazy_verification_hook(_env: sp1_sdk::HookEnv, buf: &[u8]) -> Vec<Vec<u8>> {
+    let counts = decode_lazy_verification_counts(buf);
+    if counts.is_none() {
+        println!("[HOOK] Invalid lazy verification counts.");
     }
-    vec![serialized_vec]
+    *LAZY_VERIFICATION_COUNTS.lock().unwrap() = counts;
+    vec![]
+}
+
+/// Returns and clears the counts recorded by [lazy_verification_hook], which are `None` if the
+/// guest verified the witness up front.
+pub fn take_lazy_verification_counts() -> Option<(u64, u64)> {
+    LAZY_VERIFICATION_COUNTS.lock().unwrap().take()
 }
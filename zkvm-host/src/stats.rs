@@ -9,6 +9,16 @@ pub struct BnStats {
     pub bn_mul_cycles: u64,
 }
 
+#[derive(Debug)]
+pub struct PrecompileStats {
+    pub ecrecover_cycles: u64,
+    pub sha256_cycles: u64,
+    pub ripemd160_cycles: u64,
+    pub modexp_cycles: u64,
+    pub kzg_point_evaluation_cycles: u64,
+    pub p256_verify_cycles: u64,
+}
+
 /// Statistics for the multi-block execution.
 #[derive(Debug)]
 pub struct ExecutionStats {
@@ -17,7 +27,14 @@ pub struct ExecutionStats {
     pub nb_blocks: u64,
     pub nb_transactions: u64,
     pub total_gas_used: u64,
+    pub decompression_cycles: u64,
+    pub derivation_cycles: u64,
+    pub l2_cache_update_cycles: u64,
+    pub l2_header_walk_back_cycles: u64,
+    /// The counts of preimages verified lazily and left unused, if the guest verified lazily.
+    pub lazy_verification_counts: Option<(u64, u64)>,
     pub bn_stats: BnStats,
+    pub precompile_stats: PrecompileStats,
 }
 
 /// Write a statistic to the formatter.
@@ -53,9 +70,43 @@ impl fmt::Display for ExecutionStats {
             "Block Execution Cycles",
             self.block_execution_instruction_count,
         )?;
+        write_stat(f, "Witness Decompression Cycles", self.decompression_cycles)?;
+        write_stat(f, "Derivation Cycles", self.derivation_cycles)?;
+        write_stat(f, "L2 Cache Update Cycles", self.l2_cache_update_cycles)?;
+        write_stat(
+            f,
+            "L2 Header Walk-Back Cycles",
+            self.l2_header_walk_back_cycles,
+        )?;
+        if let Some((verified, unused)) = self.lazy_verification_counts {
+            write_stat(f, "Lazily Verified Preimages", verified)?;
+            write_stat(f, "Unused Preimages", unused)?;
+        }
         write_stat(f, "Bn Pair Cycles", self.bn_stats.bn_pair_cycles)?;
         write_stat(f, "Bn Add Cycles", self.bn_stats.bn_add_cycles)?;
         write_stat(f, "Bn Mul Cycles", self.bn_stats.bn_mul_cycles)?;
+        write_stat(
+            f,
+            "Ecrecover Cycles",
+            self.precompile_stats.ecrecover_cycles,
+        )?;
+        write_stat(f, "Sha256 Cycles", self.precompile_stats.sha256_cycles)?;
+        write_stat(
+            f,
+            "Ripemd160 Cycles",
+            self.precompile_stats.ripemd160_cycles,
+        )?;
+        write_stat(f, "Modexp Cycles", self.precompile_stats.modexp_cycles)?;
+        write_stat(
+            f,
+            "KZG Point Evaluation Cycles",
+            self.precompile_stats.kzg_point_evaluation_cycles,
+        )?;
+        write_stat(
+            f,
+            "P256 Verify Cycles",
+            self.precompile_stats.p256_verify_cycles,
+        )?;
         write_stat(f, "Total Blocks", self.nb_blocks)?;
         write_stat(f, "Total Transactions", self.nb_transactions)?;
         write_stat(f, "Cycles per Block", cycles_per_block)?;